@@ -5,19 +5,55 @@ extern crate anyhow;
 
 pub mod packet;
 pub mod packet_types;
+pub mod packet_frame;
+pub mod packet_set;
+pub mod packet_version;
+pub mod packet_registry;
+pub mod packet_pipeline;
+pub mod packet_io;
+pub mod packet_connection;
 #[cfg(feature = "euclid")]
 pub mod packet_euclid;
 #[cfg(feature = "tokio-bytes")]
 pub mod packet_bytes;
+#[cfg(feature = "tokio-codec")]
+pub mod packet_codec;
+#[cfg(feature = "tokio-io")]
+pub mod packet_tokio;
+#[cfg(feature = "futures-io")]
+pub mod packet_futures;
 #[cfg(feature = "fastnbt")]
 pub mod packet_fastnbt;
+#[cfg(feature = "compression")]
+pub mod packet_compression;
+#[cfg(feature = "encryption")]
+pub mod packet_encryption;
+#[cfg(feature = "cookie")]
+pub mod packet_cookie;
 #[cfg(feature = "packet_default")]
 pub mod packet_default;
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+pub mod packet_metadata;
+#[cfg(feature = "packet_default")]
+pub mod packet_chunk;
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+pub mod packet_slot;
+#[cfg(feature = "packet_default")]
+pub mod packet_chat;
+#[cfg(feature = "packet_default")]
+pub mod packet_command;
+#[cfg(all(feature = "packet_default", feature = "chat-signing"))]
+pub mod packet_signing;
+#[cfg(all(feature = "packet_default", feature = "compression"))]
+pub mod packet_engine;
 #[cfg(test)]
 mod tests;
 
 pub use crate::packet::*;
 pub use crate::packet_types::*;
+pub use crate::packet_frame::*;
+pub use crate::packet_set::*;
+pub use crate::packet_version::*;
 #[cfg(feature = "derive")]
 pub mod derive {
     pub use bird_protocol_derive::*;
@@ -8,20 +8,31 @@ pub enum PacketReadableError {
     Any(#[from] Error),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PacketBound {
     Client,
     Server,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PacketState {
     Handshake,
     Status,
     Login,
+    /// State between login and play, exists since 1.20.2.
+    Configuration,
     Play,
 }
 
+impl PacketBound {
+    pub fn opposite(self) -> Self {
+        match self {
+            PacketBound::Client => PacketBound::Server,
+            PacketBound::Server => PacketBound::Client,
+        }
+    }
+}
+
 pub trait Packet {
     fn bound() -> PacketBound;
 
@@ -30,10 +41,79 @@ pub trait Packet {
     fn id() -> i32;
 }
 
+/// [Packet] which id depends on protocol version. Implemented by packet derive.
+pub trait VersionedPacket: Packet {
+    /// Returns id of the packet in the given protocol version or [None] if packet does not exist in it.
+    fn id_for(version: i32) -> Option<i32>;
+}
+
+/// [Packet] which state and bound are known at compile time.
+/// State and bound are types from [marker]. Implemented by packet derive.
+pub trait StatePacket<State, Bound>: Packet {}
+
+/// Type level [PacketState] and [PacketBound].
+pub mod marker {
+    use super::{PacketBound, PacketState};
+
+    pub trait StateMarker {
+        const STATE: PacketState;
+    }
+
+    pub trait BoundMarker {
+        const BOUND: PacketBound;
+
+        type Opposite: BoundMarker;
+    }
+
+    pub struct Handshake;
+
+    pub struct Status;
+
+    pub struct Login;
+
+    pub struct Configuration;
+
+    pub struct Play;
+
+    pub struct Client;
+
+    pub struct Server;
+
+    macro_rules! state_marker_impl {
+        ($($state: ident$(,)*)*) => {
+            $(
+                impl StateMarker for $state {
+                    const STATE: PacketState = PacketState::$state;
+                }
+            )*
+        }
+    }
+
+    state_marker_impl!(Handshake, Status, Login, Configuration, Play);
+
+    impl BoundMarker for Client {
+        const BOUND: PacketBound = PacketBound::Client;
+
+        type Opposite = Server;
+    }
+
+    impl BoundMarker for Server {
+        const BOUND: PacketBound = PacketBound::Server;
+
+        type Opposite = Client;
+    }
+}
+
 pub trait PacketReadable<'a>: Sized {
     fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a>;
 }
 
+/// Reading that depends on protocol version.
+/// Implemented by readable derive, fields with `since` and `until` attributes are read only in these versions.
+pub trait PacketVersionedReadable<'a>: Sized {
+    fn read_versioned<R>(read: &mut R, version: i32) -> Result<Self, PacketReadableError> where R: PacketRead<'a>;
+}
+
 pub trait PacketVariantReadable<'a, T: Sized> {
     fn read_variant<R>(read: &mut R) -> Result<T, PacketReadableError> where R: PacketRead<'a>;
 }
@@ -42,6 +122,12 @@ pub trait PacketWritable {
     fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite;
 }
 
+/// Writing that depends on protocol version.
+/// Implemented by writable derive, fields with `since` and `until` attributes are written only in these versions.
+pub trait PacketVersionedWritable {
+    fn write_versioned<W>(&self, write: &mut W, version: i32) -> Result<(), Error> where W: PacketWrite;
+}
+
 pub trait PacketVariantWritable<T: ?Sized> {
     fn write_variant<W>(object: &T, write: &mut W) -> Result<(), Error> where W: PacketWrite;
 }
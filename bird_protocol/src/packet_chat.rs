@@ -0,0 +1,169 @@
+use anyhow::Error;
+use crate::*;
+use crate::derive::*;
+
+/// Count of the last seen messages, which are acknowledged by chat messages and commands.
+pub const LAST_SEEN_MESSAGES: usize = 20;
+
+pub const MESSAGE_SIGNATURE_LENGTH: usize = 256;
+
+pub type MessageSignature = [u8; MESSAGE_SIGNATURE_LENGTH];
+
+/// Update of the last seen messages which is sent by client with chat messages, commands and acknowledgments.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LastSeenUpdate {
+    /// Count of messages which are received since the previous update.
+    #[variant(VarInt)]
+    pub offset: i32,
+    /// Bit set of the [LAST_SEEN_MESSAGES] last messages, the oldest message is the lowest bit.
+    pub acknowledged: [u8; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrackedMessage {
+    signature: MessageSignature,
+    pending: bool,
+}
+
+/// Last seen messages of the client. Client sends [LastSeenUpdate] which is generated by it.
+#[derive(Debug, Clone, Default)]
+pub struct LastSeenMessagesTracker {
+    messages: [Option<TrackedMessage>; LAST_SEEN_MESSAGES],
+    tail: usize,
+    offset: i32,
+    last_tracked: Option<MessageSignature>,
+}
+
+/// Last seen messages of the client on the server side. Validates [LastSeenUpdate] sent by client.
+#[derive(Debug, Clone)]
+pub struct LastSeenMessagesValidator {
+    messages: Vec<Option<TrackedMessage>>,
+    last_pending: Option<MessageSignature>,
+}
+
+impl LastSeenUpdate {
+    pub fn is_acknowledged(&self, index: usize) -> bool {
+        index < LAST_SEEN_MESSAGES && self.acknowledged[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_acknowledged(&mut self, index: usize) {
+        self.acknowledged[index / 8] |= 1 << (index % 8);
+    }
+}
+
+impl LastSeenMessagesTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracks received signed message. Not shown messages are tracked but never acknowledged.
+    /// Returns false if message is the same as the previous one.
+    pub fn add_pending(&mut self, signature: &MessageSignature, shown: bool) -> bool {
+        if self.last_tracked.as_ref() == Some(signature) {
+            return false;
+        }
+        self.last_tracked = Some(*signature);
+        self.messages[self.tail] = match shown {
+            true => Some(TrackedMessage { signature: *signature, pending: true }),
+            false => None,
+        };
+        self.tail = (self.tail + 1) % LAST_SEEN_MESSAGES;
+        self.offset += 1;
+        true
+    }
+
+    /// Stops tracking of the pending message, for example if it was deleted.
+    pub fn ignore_pending(&mut self, signature: &MessageSignature) {
+        if let Some(message) = self.messages.iter_mut()
+            .find(|message| matches!(message, Some(message) if message.pending && message.signature == *signature)) {
+            *message = None;
+        }
+    }
+
+    /// Count of messages which are received since the last update.
+    /// Vanilla client sends acknowledgment when it is more than 64.
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Acknowledges tracked messages and returns their signatures from the oldest to the newest with the update.
+    pub fn generate_update(&mut self) -> (Vec<MessageSignature>, LastSeenUpdate) {
+        let mut update = LastSeenUpdate { offset: self.offset, ..Default::default() };
+        let mut signatures = Vec::new();
+        self.offset = 0;
+        for index in 0..LAST_SEEN_MESSAGES {
+            if let Some(ref mut message) = self.messages[(self.tail + index) % LAST_SEEN_MESSAGES] {
+                update.set_acknowledged(index);
+                signatures.push(message.signature);
+                message.pending = false;
+            }
+        }
+        (signatures, update)
+    }
+}
+
+impl Default for LastSeenMessagesValidator {
+    fn default() -> Self {
+        Self { messages: vec![None; LAST_SEEN_MESSAGES], last_pending: None }
+    }
+}
+
+impl LastSeenMessagesValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracks message which is sent to the client.
+    pub fn add_pending(&mut self, signature: &MessageSignature) {
+        if self.last_pending.as_ref() != Some(signature) {
+            self.messages.push(Some(TrackedMessage { signature: *signature, pending: true }));
+            self.last_pending = Some(*signature);
+        }
+    }
+
+    /// Count of tracked messages, including the last seen window.
+    pub fn tracked_count(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Moves the last seen window by the offset, used for the message acknowledgment.
+    pub fn apply_offset(&mut self, offset: i32) -> Result<(), Error> {
+        let max_offset = self.messages.len() - LAST_SEEN_MESSAGES;
+        match usize::try_from(offset) {
+            Ok(offset) if offset <= max_offset => {
+                self.messages.drain(..offset);
+                Ok(())
+            }
+            _ => Err(anyhow::anyhow!(
+                "Advanced last seen window by {} messages, but expected at most {}", offset, max_offset
+            )),
+        }
+    }
+
+    /// Applies update of the chat message or command and returns signatures of acknowledged messages
+    /// from the oldest to the newest.
+    pub fn apply_update(&mut self, update: &LastSeenUpdate) -> Result<Vec<MessageSignature>, Error> {
+        self.apply_offset(update.offset)?;
+        if (LAST_SEEN_MESSAGES..24).any(|index| update.acknowledged[index / 8] & (1 << (index % 8)) != 0) {
+            return Err(anyhow::anyhow!("Last seen update contains more than {} messages", LAST_SEEN_MESSAGES));
+        }
+        let mut signatures = Vec::new();
+        for index in 0..LAST_SEEN_MESSAGES {
+            let message = &mut self.messages[index];
+            match (update.is_acknowledged(index), message) {
+                (true, Some(message)) => {
+                    message.pending = false;
+                    signatures.push(message.signature);
+                }
+                (true, None) => return Err(anyhow::anyhow!(
+                    "Last seen update acknowledged unknown or previously ignored message at index {}", index
+                )),
+                (false, Some(TrackedMessage { pending: false, .. })) => return Err(anyhow::anyhow!(
+                    "Last seen update ignored previously acknowledged message at index {}", index
+                )),
+                (false, message) => *message = None,
+            }
+        }
+        Ok(signatures)
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::Error;
+use crate::*;
+use crate::derive::*;
+
+/// Maximum bits per entry, global palette ids are at most 32 bits.
+pub const MAX_BITS_PER_ENTRY: u8 = 32;
+
+/// Kind of the [PalettedContainer]. Defines count of entries and bits of the indirect palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PalettedContainerKind {
+    /// 16x16x16 block states of the chunk section.
+    BlockStates,
+    /// 4x4x4 biomes of the chunk section.
+    Biomes,
+}
+
+/// Palette of the [PalettedContainer].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Palette {
+    /// All entries have the same value. Bits per entry is 0 and data is empty.
+    SingleValue(i32),
+    /// Data contains indexes in the palette.
+    Indirect(Vec<i32>),
+    /// Data contains global ids.
+    Direct,
+}
+
+/// Block states or biomes of the chunk section.
+/// Entries are packed into longs, entry never spans two longs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalettedContainer {
+    pub kind: PalettedContainerKind,
+    pub bits_per_entry: u8,
+    pub palette: Palette,
+    pub data: Vec<i64>,
+}
+
+/// [PalettedContainer] of [PalettedContainerKind::BlockStates].
+pub struct BlockStatesContainer;
+
+/// [PalettedContainer] of [PalettedContainerKind::Biomes].
+pub struct BiomesContainer;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq, Eq)]
+pub struct ChunkSection {
+    /// Count of non-air blocks, used by client for rendering.
+    pub block_count: i16,
+    #[variant(BlockStatesContainer)]
+    pub block_states: PalettedContainer,
+    #[variant(BiomesContainer)]
+    pub biomes: PalettedContainer,
+}
+
+impl PalettedContainerKind {
+    pub const fn entries(self) -> usize {
+        match self {
+            Self::BlockStates => 4096,
+            Self::Biomes => 64,
+        }
+    }
+
+    pub const fn min_indirect_bits(self) -> u8 {
+        match self {
+            Self::BlockStates => 4,
+            Self::Biomes => 1,
+        }
+    }
+
+    pub const fn max_indirect_bits(self) -> u8 {
+        match self {
+            Self::BlockStates => 8,
+            Self::Biomes => 3,
+        }
+    }
+
+    /// Returns whether palette is indirect ([None] for the single value) and bits of the data
+    /// for the bits per entry, the way client chooses them.
+    fn palette_bits(self, bits_per_entry: u8) -> (Option<bool>, u8) {
+        match bits_per_entry {
+            0 => (None, 0),
+            bits if bits <= self.max_indirect_bits() => (Some(true), bits.max(self.min_indirect_bits())),
+            bits => (Some(false), bits),
+        }
+    }
+}
+
+impl PalettedContainer {
+    pub fn single(kind: PalettedContainerKind, value: i32) -> Self {
+        Self { kind, bits_per_entry: 0, palette: Palette::SingleValue(value), data: Vec::new() }
+    }
+
+    /// Creates container with the smallest palette for the values.
+    /// Direct bits are the bits of the global palette, which are used if indirect palette is too big.
+    pub fn from_values(kind: PalettedContainerKind, values: &[i32], direct_bits: u8) -> Result<Self, Error> {
+        if values.len() != kind.entries() {
+            return Err(Error::msg("Bad count of paletted container values"));
+        }
+        if direct_bits > MAX_BITS_PER_ENTRY {
+            return Err(anyhow::anyhow!("Too many bits per entry: {}", direct_bits));
+        }
+        let mut palette: Vec<i32> = Vec::new();
+        for value in values {
+            if !palette.contains(value) {
+                palette.push(*value);
+            }
+        }
+        if palette.len() == 1 {
+            return Ok(Self::single(kind, palette[0]));
+        }
+        let bits = (usize::BITS - (palette.len() - 1).leading_zeros()) as u8;
+        if bits <= kind.max_indirect_bits() {
+            let bits = bits.max(kind.min_indirect_bits());
+            let indexes = values.iter()
+                .map(|value| palette.iter().position(|entry| entry == value).unwrap() as u64);
+            return Ok(Self {
+                kind,
+                bits_per_entry: bits,
+                data: pack(indexes, bits, kind.entries()),
+                palette: Palette::Indirect(palette),
+            });
+        }
+        if direct_bits == 0 || values.iter().any(|value| (*value as u32 as u64) >> direct_bits != 0) {
+            return Err(Error::msg("Value does not fit into the direct palette"));
+        }
+        let values = values.iter().map(|value| *value as u32 as u64);
+        Ok(Self {
+            kind,
+            bits_per_entry: direct_bits,
+            data: pack(values, direct_bits, kind.entries()),
+            palette: Palette::Direct,
+        })
+    }
+
+    /// Returns value of the entry by its index, which is `(y * 16 + z) * 16 + x` for block states
+    /// and `(y * 4 + z) * 4 + x` for biomes.
+    pub fn get(&self, index: usize) -> Option<i32> {
+        if index >= self.kind.entries() {
+            return None;
+        }
+        match self.palette {
+            Palette::SingleValue(value) => Some(value),
+            Palette::Indirect(ref palette) => palette.get(unpack(&self.data, self.bits_per_entry, index)? as usize).copied(),
+            Palette::Direct => unpack(&self.data, self.bits_per_entry, index).map(|value| value as i32),
+        }
+    }
+
+    /// Returns values of all entries.
+    pub fn values(&self) -> Result<Vec<i32>, Error> {
+        (0..self.kind.entries())
+            .map(|index| self.get(index).ok_or_else(|| Error::msg("Bad paletted container data")))
+            .collect()
+    }
+
+    fn read<'a, R>(kind: PalettedContainerKind, read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let bits_per_entry = u8::read(read)?;
+        if bits_per_entry > MAX_BITS_PER_ENTRY {
+            return Err(PacketReadableError::Any(anyhow::anyhow!("Too many bits per entry: {}", bits_per_entry)));
+        }
+        let (indirect, bits) = kind.palette_bits(bits_per_entry);
+        let palette = match indirect {
+            None => Palette::SingleValue(VarInt::read_variant(read)?),
+            Some(true) => {
+                let length: i32 = VarInt::read_variant(read)?;
+                let mut palette = Vec::with_capacity(length.clamp(0, 256) as usize);
+                for _ in 0..length {
+                    palette.push(VarInt::read_variant(read)?);
+                }
+                Palette::Indirect(palette)
+            }
+            Some(false) => Palette::Direct,
+        };
+        let length: i32 = VarInt::read_variant(read)?;
+        if length < 0 || length as usize != packed_length(bits, kind.entries()) {
+            return Err(PacketReadableError::Any(Error::msg("Bad length of paletted container data")));
+        }
+        let mut data = Vec::with_capacity(length as usize);
+        for _ in 0..length {
+            data.push(i64::read(read)?);
+        }
+        Ok(Self { kind, bits_per_entry: bits, palette, data })
+    }
+
+    fn write<W>(&self, kind: PalettedContainerKind, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        if self.bits_per_entry > MAX_BITS_PER_ENTRY {
+            return Err(anyhow::anyhow!("Too many bits per entry: {}", self.bits_per_entry));
+        }
+        if self.kind != kind || kind.palette_bits(self.bits_per_entry).1 != self.bits_per_entry ||
+            self.data.len() != packed_length(self.bits_per_entry, kind.entries()) {
+            return Err(Error::msg("Paletted container is not valid"));
+        }
+        self.bits_per_entry.write(write)?;
+        match (&self.palette, kind.palette_bits(self.bits_per_entry).0) {
+            (Palette::SingleValue(value), None) => VarInt::write_variant(value, write)?,
+            (Palette::Indirect(palette), Some(true)) => {
+                VarInt::write_variant(&(palette.len() as i32), write)?;
+                for value in palette {
+                    VarInt::write_variant(value, write)?;
+                }
+            }
+            (Palette::Direct, Some(false)) => {}
+            _ => return Err(Error::msg("Palette does not match bits per entry")),
+        }
+        VarInt::write_variant(&(self.data.len() as i32), write)?;
+        self.data.iter().try_for_each(|long| long.write(write))
+    }
+}
+
+impl ChunkSection {
+    /// Reads sections from the data of the chunk data packet.
+    pub fn read_all(data: &[u8]) -> Result<Vec<Self>, PacketReadableError> {
+        let mut read = SlicePacketRead::new(data);
+        let mut sections = Vec::new();
+        while read.available() != 0 {
+            sections.push(Self::read(&mut read)?);
+        }
+        Ok(sections)
+    }
+
+    /// Writes sections as the data of the chunk data packet.
+    pub fn write_all(sections: &[Self]) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        for section in sections {
+            section.write(&mut data)?;
+        }
+        Ok(data)
+    }
+}
+
+fn packed_length(bits: u8, entries: usize) -> usize {
+    match bits {
+        0 => 0,
+        bits => entries.div_ceil(64 / bits as usize),
+    }
+}
+
+fn pack(values: impl Iterator<Item=u64>, bits: u8, entries: usize) -> Vec<i64> {
+    let per_long = 64 / bits as usize;
+    let mut data = vec![0u64; packed_length(bits, entries)];
+    for (index, value) in values.enumerate() {
+        data[index / per_long] |= value << (index % per_long * bits as usize);
+    }
+    data.into_iter().map(|long| long as i64).collect()
+}
+
+fn unpack(data: &[i64], bits: u8, index: usize) -> Option<u64> {
+    if bits == 0 || bits > 32 {
+        return None;
+    }
+    let per_long = 64 / bits as usize;
+    let long = *data.get(index / per_long)? as u64;
+    Some((long >> (index % per_long * bits as usize)) & ((1u64 << bits) - 1))
+}
+
+macro_rules! container_variant_impl {
+    ($($marker: ident => $kind: ident$(,)*)*) => {
+        $(
+            impl<'a> PacketVariantReadable<'a, PalettedContainer> for $marker {
+                fn read_variant<R>(read: &mut R) -> Result<PalettedContainer, PacketReadableError> where R: PacketRead<'a> {
+                    PalettedContainer::read(PalettedContainerKind::$kind, read)
+                }
+            }
+
+            impl PacketVariantWritable<PalettedContainer> for $marker {
+                fn write_variant<W>(object: &PalettedContainer, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+                    object.write(PalettedContainerKind::$kind, write)
+                }
+            }
+        )*
+    }
+}
+
+container_variant_impl!(BlockStatesContainer => BlockStates, BiomesContainer => Biomes);
@@ -0,0 +1,95 @@
+use anyhow::Error;
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+use crate::packet::{Packet, PacketWritable};
+use crate::packet_compression::PacketCompression;
+use crate::packet_encryption::PacketCipher;
+use crate::packet_frame::{PacketFrame, PacketFrameBuf};
+use crate::packet_pipeline::PacketPipeline;
+
+/// Codec for [tokio_util::codec::Framed].
+///
+/// Decodes frames as [PacketFrameBuf], encodes packets and already read frames.
+/// Compression and encryption can be changed between reads and writes.
+#[derive(Default)]
+pub struct PacketCodec {
+    pipeline: PacketPipeline,
+}
+
+impl PacketCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pipeline(&self) -> &PacketPipeline {
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut PacketPipeline {
+        &mut self.pipeline
+    }
+
+    pub fn compression(&self) -> Option<&PacketCompression> {
+        self.pipeline.compression()
+    }
+
+    pub fn set_compression(&mut self, compression: Option<PacketCompression>) {
+        self.pipeline.set_compression(compression)
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.pipeline.is_encrypted()
+    }
+
+    pub fn enable_encryption(&mut self, cipher: PacketCipher) {
+        self.pipeline.enable_encryption(cipher)
+    }
+}
+
+impl Decoder for PacketCodec {
+    type Item = PacketFrameBuf;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if !src.is_empty() {
+            self.pipeline.receive(&src.split());
+        }
+        Ok(self.pipeline.next_frame()?)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None => match self.pipeline.buffered() {
+                0 => Ok(None),
+                _ => Err(Error::msg("Bytes remaining on stream")),
+            }
+        }
+    }
+}
+
+impl<T: Packet + PacketWritable> Encoder<&T> for PacketCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.pipeline.write_packet(item, dst)
+    }
+}
+
+impl Encoder<PacketFrame<'_>> for PacketCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: PacketFrame<'_>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let mut data = Vec::with_capacity(item.body.len() + 5);
+        item.write_data(&mut data)?;
+        self.pipeline.write_data(&data, dst)
+    }
+}
+
+impl Encoder<PacketFrameBuf> for PacketCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: PacketFrameBuf, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode(item.as_frame(), dst)
+    }
+}
@@ -0,0 +1,526 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use anyhow::Error;
+use bird_chat::identifier::Identifier;
+use crate::*;
+use crate::derive::*;
+
+const NODE_TYPE_MASK: u8 = 0x03;
+const NODE_ROOT: u8 = 0;
+const NODE_LITERAL: u8 = 1;
+const NODE_ARGUMENT: u8 = 2;
+const NODE_EXECUTABLE: u8 = 0x04;
+const NODE_REDIRECT: u8 = 0x08;
+const NODE_SUGGESTIONS: u8 = 0x10;
+
+const RANGE_MIN: u8 = 0x01;
+const RANGE_MAX: u8 = 0x02;
+
+const ENTITY_SINGLE: u8 = 0x01;
+const ENTITY_PLAYERS_ONLY: u8 = 0x02;
+
+const SCORE_HOLDER_MULTIPLE: u8 = 0x01;
+
+const PARSER_BOOL: i32 = 0;
+const PARSER_FLOAT: i32 = 1;
+const PARSER_DOUBLE: i32 = 2;
+const PARSER_INTEGER: i32 = 3;
+const PARSER_LONG: i32 = 4;
+const PARSER_STRING: i32 = 5;
+const PARSER_ENTITY: i32 = 6;
+const PARSER_SCORE_HOLDER: i32 = 30;
+const PARSER_TIME: i32 = 42;
+const PARSER_RESOURCE_OR_TAG: i32 = 43;
+const PARSER_RESOURCE_OR_TAG_KEY: i32 = 44;
+const PARSER_RESOURCE: i32 = 45;
+const PARSER_RESOURCE_KEY: i32 = 46;
+
+/// Maximum depth of the command node from the root, deeper graphs are rejected
+/// so reading and dropping of the tree can not overflow the stack.
+pub const MAX_COMMAND_DEPTH: usize = 256;
+
+type ChildrenArray = LengthProvidedSlice<VarInt, VarInt, i32, i32>;
+type FlatCommandNodeArray<'a> = LengthProvidedSlice<VarInt, FlatCommandNode<'a>, i32>;
+
+/// Kind of string which is parsed by `brigadier:string`.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum StringKind {
+    SingleWord,
+    /// Single word or the quoted string.
+    QuotablePhrase,
+    /// Rest of the command.
+    GreedyPhrase,
+}
+
+/// Parser of the argument node with its properties.
+/// Ids are of the `minecraft:command_argument_type` registry of 1.20.5.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentParser<'a> {
+    Bool,
+    Float { min: Option<f32>, max: Option<f32> },
+    Double { min: Option<f64>, max: Option<f64> },
+    Integer { min: Option<i32>, max: Option<i32> },
+    Long { min: Option<i64>, max: Option<i64> },
+    String(StringKind),
+    /// `minecraft:entity`
+    Entity { single: bool, players_only: bool },
+    /// `minecraft:score_holder`
+    ScoreHolder { allow_multiple: bool },
+    /// `minecraft:time`, minimal count of ticks.
+    Time { min: i32 },
+    /// `minecraft:resource_or_tag`
+    ResourceOrTag { registry: Identifier<'a> },
+    /// `minecraft:resource_or_tag_key`
+    ResourceOrTagKey { registry: Identifier<'a> },
+    /// `minecraft:resource`
+    Resource { registry: Identifier<'a> },
+    /// `minecraft:resource_key`
+    ResourceKey { registry: Identifier<'a> },
+    /// Parser without typed properties. Properties are written as is,
+    /// other parsers of 1.20.5 have no properties, so they are always read empty.
+    Raw { id: i32, properties: Cow<'a, [u8]> },
+}
+
+/// Kind of the command node with its data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandNodeKind<'a> {
+    Root,
+    Literal(Cow<'a, str>),
+    Argument {
+        name: Cow<'a, str>,
+        parser: ArgumentParser<'a>,
+        /// Suggestions which are asked from the server or computed by client,
+        /// for example `minecraft:ask_server`.
+        suggestions: Option<Identifier<'a>>,
+    },
+}
+
+/// Node of the [CommandGraph] with its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandNode<'a> {
+    pub kind: CommandNodeKind<'a>,
+    /// Command can be executed if it ends on this node.
+    pub executable: bool,
+    /// Path of the node from the root, which parses the rest of the command.
+    /// Empty path is the root, for example `execute run` is redirected to it.
+    pub redirect: Option<Vec<Cow<'a, str>>>,
+    pub children: Vec<CommandNode<'a>>,
+}
+
+/// Brigadier command tree of the commands packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandGraph<'a> {
+    pub root: CommandNode<'a>,
+}
+
+/// Command node how it is sent, children and redirect are indexes in [FlatCommandGraph::nodes].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatCommandNode<'a> {
+    pub kind: CommandNodeKind<'a>,
+    pub executable: bool,
+    pub redirect: Option<i32>,
+    pub children: Vec<i32>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct FlatCommandGraph<'a> {
+    #[variant(FlatCommandNodeArray)]
+    pub nodes: Vec<FlatCommandNode<'a>>,
+    #[variant(VarInt)]
+    pub root_index: i32,
+}
+
+impl ArgumentParser<'_> {
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Bool => PARSER_BOOL,
+            Self::Float { .. } => PARSER_FLOAT,
+            Self::Double { .. } => PARSER_DOUBLE,
+            Self::Integer { .. } => PARSER_INTEGER,
+            Self::Long { .. } => PARSER_LONG,
+            Self::String(_) => PARSER_STRING,
+            Self::Entity { .. } => PARSER_ENTITY,
+            Self::ScoreHolder { .. } => PARSER_SCORE_HOLDER,
+            Self::Time { .. } => PARSER_TIME,
+            Self::ResourceOrTag { .. } => PARSER_RESOURCE_OR_TAG,
+            Self::ResourceOrTagKey { .. } => PARSER_RESOURCE_OR_TAG_KEY,
+            Self::Resource { .. } => PARSER_RESOURCE,
+            Self::ResourceKey { .. } => PARSER_RESOURCE_KEY,
+            Self::Raw { id, .. } => *id,
+        }
+    }
+
+    /// Parser without properties.
+    pub const fn raw(id: i32) -> Self {
+        Self::Raw { id, properties: Cow::Borrowed(&[]) }
+    }
+}
+
+impl<'a> CommandNode<'a> {
+    pub fn root() -> Self {
+        Self::new(CommandNodeKind::Root)
+    }
+
+    pub fn literal(name: impl Into<Cow<'a, str>>) -> Self {
+        Self::new(CommandNodeKind::Literal(name.into()))
+    }
+
+    pub fn argument(name: impl Into<Cow<'a, str>>, parser: ArgumentParser<'a>) -> Self {
+        Self::new(CommandNodeKind::Argument { name: name.into(), parser, suggestions: None })
+    }
+
+    fn new(kind: CommandNodeKind<'a>) -> Self {
+        Self { kind, executable: false, redirect: None, children: Vec::new() }
+    }
+
+    /// Name of the literal or argument node.
+    pub fn name(&self) -> Option<&str> {
+        match self.kind {
+            CommandNodeKind::Root => None,
+            CommandNodeKind::Literal(ref name) | CommandNodeKind::Argument { ref name, .. } => Some(name),
+        }
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Self> {
+        self.children.iter().find(|child| child.name() == Some(name))
+    }
+
+    pub fn then(mut self, child: Self) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn executes(mut self) -> Self {
+        self.executable = true;
+        self
+    }
+
+    pub fn redirect<S: Into<Cow<'a, str>>>(mut self, path: impl IntoIterator<Item=S>) -> Self {
+        self.redirect = Some(path.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets suggestions of the argument node, does nothing for other nodes.
+    pub fn suggests(mut self, suggestions: Identifier<'a>) -> Self {
+        if let CommandNodeKind::Argument { suggestions: ref mut current, .. } = self.kind {
+            *current = Some(suggestions);
+        }
+        self
+    }
+}
+
+impl Default for CommandGraph<'_> {
+    fn default() -> Self {
+        Self { root: CommandNode::root() }
+    }
+}
+
+impl<'a> CommandGraph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds command to the root.
+    pub fn then(mut self, child: CommandNode<'a>) -> Self {
+        self.root.children.push(child);
+        self
+    }
+
+    /// Returns node by its path from the root.
+    pub fn find(&self, path: &[&str]) -> Option<&CommandNode<'a>> {
+        path.iter().try_fold(&self.root, |node, name| node.child(name))
+    }
+
+    /// Flattens tree, the root is the first node and children are following their parents.
+    pub fn flatten(&self) -> Result<FlatCommandGraph<'a>, Error> {
+        if self.root.kind != CommandNodeKind::Root {
+            return Err(Error::msg("Command graph root is not a root node"));
+        }
+        let mut nodes = Vec::new();
+        let mut indexes = HashMap::new();
+        let mut redirects = Vec::new();
+        flatten_node(&self.root, &mut Vec::new(), &mut nodes, &mut indexes, &mut redirects)?;
+        for (index, path) in redirects {
+            let path: Vec<&str> = path.iter().map(|name| name.as_ref()).collect();
+            nodes[index].redirect = Some(*indexes.get(&path)
+                .ok_or_else(|| anyhow::anyhow!("Redirect to unknown command node {}", path.join(" ")))?);
+        }
+        Ok(FlatCommandGraph { nodes, root_index: 0 })
+    }
+
+    /// Builds tree from the flattened graph. Nodes which are not reachable from the root are skipped.
+    pub fn unflatten(graph: &FlatCommandGraph<'a>) -> Result<Self, Error> {
+        let root_index = usize::try_from(graph.root_index).ok()
+            .filter(|index| *index < graph.nodes.len())
+            .ok_or_else(|| Error::msg("Bad command graph root index"))?;
+        if graph.nodes[root_index].kind != CommandNodeKind::Root {
+            return Err(Error::msg("Command graph root is not a root node"));
+        }
+        // Parents and depths of the reachable nodes, every node except the root has exactly one parent
+        let mut parents: Vec<Option<(usize, usize)>> = vec![None; graph.nodes.len()];
+        parents[root_index] = Some((root_index, 0));
+        let mut queue = vec![root_index];
+        while let Some(index) = queue.pop() {
+            let depth = parents[index].unwrap().1 + 1;
+            if depth > MAX_COMMAND_DEPTH && !graph.nodes[index].children.is_empty() {
+                return Err(anyhow::anyhow!("Command graph is deeper than {}", MAX_COMMAND_DEPTH));
+            }
+            for child_index in &graph.nodes[index].children {
+                let child_index = usize::try_from(*child_index).ok()
+                    .filter(|child_index| *child_index < graph.nodes.len())
+                    .ok_or_else(|| anyhow::anyhow!("Bad child index {}", child_index))?;
+                if graph.nodes[child_index].kind == CommandNodeKind::Root {
+                    return Err(Error::msg("Root node is a child"));
+                }
+                if parents[child_index].is_some() {
+                    return Err(anyhow::anyhow!("Command node {} has more than one parent", child_index));
+                }
+                parents[child_index] = Some((index, depth));
+                queue.push(child_index);
+            }
+        }
+        Ok(Self { root: unflatten_node(graph, &parents, root_index)? })
+    }
+}
+
+/// Path from the root to the reachable node, which is built by going up through the parents.
+fn node_path<'a>(
+    graph: &FlatCommandGraph<'a>, parents: &[Option<(usize, usize)>], mut index: usize,
+) -> Vec<Cow<'a, str>> {
+    let mut path = Vec::new();
+    loop {
+        let name = match graph.nodes[index].kind {
+            CommandNodeKind::Root => break,
+            CommandNodeKind::Literal(ref name) | CommandNodeKind::Argument { ref name, .. } => name,
+        };
+        path.push(name.clone());
+        index = parents[index].unwrap().0;
+    }
+    path.reverse();
+    path
+}
+
+fn flatten_node<'a, 'b>(
+    node: &'b CommandNode<'a>,
+    path: &mut Vec<&'b str>,
+    nodes: &mut Vec<FlatCommandNode<'a>>,
+    indexes: &mut HashMap<Vec<&'b str>, i32>,
+    redirects: &mut Vec<(usize, &'b [Cow<'a, str>])>,
+) -> Result<i32, Error> {
+    if path.len() > MAX_COMMAND_DEPTH {
+        return Err(anyhow::anyhow!("Command graph is deeper than {}", MAX_COMMAND_DEPTH));
+    }
+    let index = nodes.len();
+    nodes.push(FlatCommandNode {
+        kind: node.kind.clone(),
+        executable: node.executable,
+        redirect: None,
+        children: Vec::with_capacity(node.children.len()),
+    });
+    indexes.insert(path.clone(), index as i32);
+    if let Some(ref redirect) = node.redirect {
+        redirects.push((index, redirect));
+    }
+    for child in &node.children {
+        let name = child.name().ok_or_else(|| Error::msg("Root node is a child"))?;
+        path.push(name);
+        if indexes.contains_key(path) {
+            return Err(anyhow::anyhow!("Duplicate command node {}", path.join(" ")));
+        }
+        let child_index = flatten_node(child, path, nodes, indexes, redirects)?;
+        path.pop();
+        nodes[index].children.push(child_index);
+    }
+    Ok(index as i32)
+}
+
+fn unflatten_node<'a>(
+    graph: &FlatCommandGraph<'a>,
+    parents: &[Option<(usize, usize)>],
+    index: usize,
+) -> Result<CommandNode<'a>, Error> {
+    let node = &graph.nodes[index];
+    let redirect = match node.redirect {
+        Some(redirect) => Some(usize::try_from(redirect).ok()
+            .filter(|redirect| matches!(parents.get(*redirect), Some(Some(_))))
+            .map(|redirect| node_path(graph, parents, redirect))
+            .ok_or_else(|| anyhow::anyhow!("Redirect to unknown command node {}", redirect))?),
+        None => None,
+    };
+    Ok(CommandNode {
+        kind: node.kind.clone(),
+        executable: node.executable,
+        redirect,
+        children: node.children.iter()
+            .map(|child_index| unflatten_node(graph, parents, *child_index as usize))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn read_range<'a, T, R>(read: &mut R) -> Result<(Option<T>, Option<T>), PacketReadableError>
+    where T: PacketReadable<'a>, R: PacketRead<'a> {
+    let flags = u8::read(read)?;
+    let min = match flags & RANGE_MIN != 0 {
+        true => Some(T::read(read)?),
+        false => None,
+    };
+    let max = match flags & RANGE_MAX != 0 {
+        true => Some(T::read(read)?),
+        false => None,
+    };
+    Ok((min, max))
+}
+
+fn write_range<T, W>(min: &Option<T>, max: &Option<T>, write: &mut W) -> Result<(), Error>
+    where T: PacketWritable, W: PacketWrite {
+    let mut flags = 0;
+    if min.is_some() {
+        flags |= RANGE_MIN;
+    }
+    if max.is_some() {
+        flags |= RANGE_MAX;
+    }
+    flags.write(write)?;
+    min.iter().chain(max.iter()).try_for_each(|value| value.write(write))
+}
+
+impl<'a> PacketReadable<'a> for ArgumentParser<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Ok(match VarInt::read_variant(read)? {
+            PARSER_BOOL => Self::Bool,
+            PARSER_FLOAT => {
+                let (min, max) = read_range(read)?;
+                Self::Float { min, max }
+            }
+            PARSER_DOUBLE => {
+                let (min, max) = read_range(read)?;
+                Self::Double { min, max }
+            }
+            PARSER_INTEGER => {
+                let (min, max) = read_range(read)?;
+                Self::Integer { min, max }
+            }
+            PARSER_LONG => {
+                let (min, max) = read_range(read)?;
+                Self::Long { min, max }
+            }
+            PARSER_STRING => Self::String(StringKind::read(read)?),
+            PARSER_ENTITY => {
+                let flags = u8::read(read)?;
+                Self::Entity {
+                    single: flags & ENTITY_SINGLE != 0,
+                    players_only: flags & ENTITY_PLAYERS_ONLY != 0,
+                }
+            }
+            PARSER_SCORE_HOLDER => Self::ScoreHolder { allow_multiple: u8::read(read)? & SCORE_HOLDER_MULTIPLE != 0 },
+            PARSER_TIME => Self::Time { min: i32::read(read)? },
+            PARSER_RESOURCE_OR_TAG => Self::ResourceOrTag { registry: Identifier::read(read)? },
+            PARSER_RESOURCE_OR_TAG_KEY => Self::ResourceOrTagKey { registry: Identifier::read(read)? },
+            PARSER_RESOURCE => Self::Resource { registry: Identifier::read(read)? },
+            PARSER_RESOURCE_KEY => Self::ResourceKey { registry: Identifier::read(read)? },
+            id => Self::raw(id),
+        })
+    }
+}
+
+impl PacketWritable for ArgumentParser<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.id(), write)?;
+        match self {
+            Self::Bool => Ok(()),
+            Self::Float { min, max } => write_range(min, max, write),
+            Self::Double { min, max } => write_range(min, max, write),
+            Self::Integer { min, max } => write_range(min, max, write),
+            Self::Long { min, max } => write_range(min, max, write),
+            Self::String(kind) => kind.write(write),
+            Self::Entity { single, players_only } => {
+                let mut flags = 0;
+                if *single {
+                    flags |= ENTITY_SINGLE;
+                }
+                if *players_only {
+                    flags |= ENTITY_PLAYERS_ONLY;
+                }
+                flags.write(write)
+            }
+            Self::ScoreHolder { allow_multiple } => match allow_multiple {
+                true => SCORE_HOLDER_MULTIPLE,
+                false => 0,
+            }.write(write),
+            Self::Time { min } => min.write(write),
+            Self::ResourceOrTag { registry } | Self::ResourceOrTagKey { registry } |
+            Self::Resource { registry } | Self::ResourceKey { registry } => registry.write(write),
+            Self::Raw { properties, .. } => write.write_bytes(properties),
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for FlatCommandNode<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let flags = u8::read(read)?;
+        let children = ChildrenArray::read_variant(read)?;
+        let redirect = match flags & NODE_REDIRECT != 0 {
+            true => Some(VarInt::read_variant(read)?),
+            false => None,
+        };
+        let kind = match flags & NODE_TYPE_MASK {
+            NODE_ROOT => CommandNodeKind::Root,
+            NODE_LITERAL => CommandNodeKind::Literal(Cow::read(read)?),
+            NODE_ARGUMENT => CommandNodeKind::Argument {
+                name: Cow::read(read)?,
+                parser: ArgumentParser::read(read)?,
+                suggestions: match flags & NODE_SUGGESTIONS != 0 {
+                    true => Some(Identifier::read(read)?),
+                    false => None,
+                },
+            },
+            node_type => return Err(PacketReadableError::Any(anyhow::anyhow!("Unknown command node type {}", node_type))),
+        };
+        Ok(Self { kind, executable: flags & NODE_EXECUTABLE != 0, redirect, children })
+    }
+}
+
+impl PacketWritable for FlatCommandNode<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        let mut flags = match self.kind {
+            CommandNodeKind::Root => NODE_ROOT,
+            CommandNodeKind::Literal(_) => NODE_LITERAL,
+            CommandNodeKind::Argument { suggestions: Some(_), .. } => NODE_ARGUMENT | NODE_SUGGESTIONS,
+            CommandNodeKind::Argument { suggestions: None, .. } => NODE_ARGUMENT,
+        };
+        if self.executable {
+            flags |= NODE_EXECUTABLE;
+        }
+        if self.redirect.is_some() {
+            flags |= NODE_REDIRECT;
+        }
+        flags.write(write)?;
+        ChildrenArray::write_variant(&self.children, write)?;
+        if let Some(ref redirect) = self.redirect {
+            VarInt::write_variant(redirect, write)?;
+        }
+        match self.kind {
+            CommandNodeKind::Root => Ok(()),
+            CommandNodeKind::Literal(ref name) => name.write(write),
+            CommandNodeKind::Argument { ref name, ref parser, ref suggestions } => {
+                name.write(write)?;
+                parser.write(write)?;
+                suggestions.iter().try_for_each(|suggestions| suggestions.write(write))
+            }
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for CommandGraph<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Self::unflatten(&FlatCommandGraph::read(read)?).map_err(PacketReadableError::Any)
+    }
+}
+
+impl PacketWritable for CommandGraph<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        self.flatten()?.write(write)
+    }
+}
@@ -0,0 +1,105 @@
+use std::borrow::Cow;
+use std::io::{Read, Write};
+use anyhow::Error;
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use crate::packet::{Packet, PacketReadableError, PacketVariantReadable, PacketVariantWritable, PacketWritable, PacketWrite, PacketRead, SlicePacketRead};
+use crate::packet_frame::{packet_data, write_frame};
+use crate::packet_types::VarInt;
+
+/// Maximum length of decompressed packet data that is accepted by vanilla.
+pub const MAX_DATA_LENGTH: usize = 8388608;
+
+/// Compressed packet format. Packet data is prefixed with its length as [VarInt]
+/// and compressed with zlib if this length reached the threshold,
+/// otherwise length is zero and data is not compressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacketCompression {
+    pub threshold: usize,
+    pub max_data_length: usize,
+}
+
+impl PacketCompression {
+    /// Creates compression with the given threshold. Negative threshold disables compression.
+    pub fn new(threshold: i32) -> Option<Self> {
+        match threshold < 0 {
+            true => None,
+            false => Some(Self {
+                threshold: threshold as usize,
+                max_data_length: MAX_DATA_LENGTH,
+            })
+        }
+    }
+
+    #[cfg(feature = "packet_default")]
+    pub fn from_packet(packet: &crate::packet_default::LoginSetCompression) -> Option<Self> {
+        Self::new(packet.threshold)
+    }
+
+    pub fn with_max_data_length(self, max_data_length: usize) -> Self {
+        Self { max_data_length, ..self }
+    }
+
+    /// Returns packet data (id and packet itself) of the compressed frame.
+    pub fn decompress<'a>(&self, frame: &'a [u8]) -> Result<Cow<'a, [u8]>, PacketReadableError> {
+        let mut read = SlicePacketRead::new(frame);
+        let data_length = VarInt::read_variant(&mut read)?;
+        let data = read.take_slice(read.available())?;
+        if data_length == 0 {
+            return match data.len() >= self.threshold {
+                true => Err(PacketReadableError::Any(Error::msg("Uncompressed packet is bigger than threshold"))),
+                false => Ok(Cow::Borrowed(data)),
+            };
+        }
+        if data_length < 0 || (data_length as usize) < self.threshold {
+            return Err(PacketReadableError::Any(Error::msg("Compressed packet is smaller than threshold")));
+        }
+        let data_length = data_length as usize;
+        if data_length > self.max_data_length {
+            return Err(PacketReadableError::Any(Error::msg("Too big compressed packet")));
+        }
+        let mut result = Vec::with_capacity(data_length);
+        // Reading one byte more to know that data is bigger than it was declared
+        ZlibDecoder::new(data)
+            .take(data_length as u64 + 1)
+            .read_to_end(&mut result)
+            .map_err(|err| PacketReadableError::Any(err.into()))?;
+        match result.len() == data_length {
+            true => Ok(Cow::Owned(result)),
+            false => Err(PacketReadableError::Any(Error::msg("Bad length of decompressed packet"))),
+        }
+    }
+
+    /// Writes packet data in the compressed format without length prefix.
+    pub fn compress<W>(&self, data: &[u8], write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        if data.len() > self.max_data_length {
+            return Err(Error::msg("Too big packet to compress"));
+        }
+        match data.len() >= self.threshold {
+            true => {
+                VarInt::write_variant(&(data.len() as i32), write)?;
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data)?;
+                write.write_bytes_owned(encoder.finish()?)
+            }
+            false => {
+                VarInt::write_variant(&0, write)?;
+                write.write_bytes(data)
+            }
+        }
+    }
+
+    /// Writes packet data in the compressed format with length prefix.
+    pub fn write_frame<W>(&self, data: &[u8], write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        let mut frame = Vec::new();
+        self.compress(data, &mut frame)?;
+        write_frame(&frame, write)
+    }
+
+    /// Writes packet with its id in the compressed format with length prefix.
+    pub fn write_packet_frame<T, W>(&self, packet: &T, write: &mut W) -> Result<(), Error>
+        where T: Packet + PacketWritable, W: PacketWrite {
+        self.write_frame(&packet_data(packet)?, write)
+    }
+}
@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+use anyhow::Error;
+use crate::packet::{PacketReadable, PacketReadableError, PacketWritable, PacketWrite, StatePacket};
+use crate::packet::marker::{BoundMarker, Configuration, Handshake, Login, Play, StateMarker, Status};
+use crate::packet_frame::{PacketFrame, PacketFrameBuf};
+use crate::packet_pipeline::PacketPipeline;
+
+/// Connection which state is checked at compile time.
+///
+/// `Side` is the bound of packets that are received by this side,
+/// so client is `Connection<_, Client, _>` and it sends packets with [crate::packet::marker::Server] bound.
+/// Packets of another state or bound can not be sent:
+///
+/// ```compile_fail
+/// use bird_protocol::packet::marker::{Client, Status};
+/// use bird_protocol::packet_connection::Connection;
+/// use bird_protocol::packet_default::LoginStart;
+///
+/// let mut connection: Connection<Status, Client, Vec<u8>> = Connection::new(Vec::new()).into_status();
+/// connection.send(&LoginStart { name: "jenya705", signature_data: None }).unwrap();
+/// ```
+pub struct Connection<State: StateMarker, Side: BoundMarker, W: PacketWrite> {
+    write: W,
+    pipeline: PacketPipeline,
+    _marker: PhantomData<(State, Side)>,
+}
+
+impl<Side: BoundMarker, W: PacketWrite> Connection<Handshake, Side, W> {
+    pub fn new(write: W) -> Self {
+        Self::with_pipeline(write, PacketPipeline::new())
+    }
+
+    pub fn into_status(self) -> Connection<Status, Side, W> {
+        self.transition()
+    }
+
+    /// Changes state to login. Used for both login and transfer handshake intents.
+    pub fn into_login(self) -> Connection<Login, Side, W> {
+        self.transition()
+    }
+}
+
+impl<Side: BoundMarker, W: PacketWrite> Connection<Login, Side, W> {
+    /// Changes state to play. Login success packet must be sent or received before.
+    /// Since 1.20.2 [Connection::into_configuration] should be used instead.
+    #[cfg(feature = "packet_default")]
+    pub fn into_play(self, _success: &crate::packet_default::LoginSuccess) -> Connection<Play, Side, W> {
+        self.transition()
+    }
+
+    /// Changes state to configuration. Login acknowledged packet must be sent or received before.
+    #[cfg(feature = "packet_default")]
+    pub fn into_configuration(
+        self, _acknowledged: &crate::packet_default::LoginAcknowledged,
+    ) -> Connection<Configuration, Side, W> {
+        self.transition()
+    }
+}
+
+impl<Side: BoundMarker, W: PacketWrite> Connection<Configuration, Side, W> {
+    /// Changes state to play. Finish configuration must be acknowledged before.
+    #[cfg(feature = "packet_default")]
+    pub fn into_play(
+        self, _acknowledged: &crate::packet_default::ConfigurationFinishAcknowledged,
+    ) -> Connection<Play, Side, W> {
+        self.transition()
+    }
+}
+
+impl<State: StateMarker, Side: BoundMarker, W: PacketWrite> Connection<State, Side, W> {
+    pub fn with_pipeline(write: W, pipeline: PacketPipeline) -> Self {
+        Self { write, pipeline, _marker: PhantomData }
+    }
+
+    fn transition<NewState: StateMarker>(self) -> Connection<NewState, Side, W> {
+        Connection::with_pipeline(self.write, self.pipeline)
+    }
+
+    pub fn pipeline(&self) -> &PacketPipeline {
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut PacketPipeline {
+        &mut self.pipeline
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.write
+    }
+
+    pub fn into_inner(self) -> (W, PacketPipeline) {
+        (self.write, self.pipeline)
+    }
+
+    pub fn send<P>(&mut self, packet: &P) -> Result<(), Error>
+        where P: StatePacket<State, Side::Opposite> + PacketWritable {
+        self.pipeline.write_packet(packet, &mut self.write)
+    }
+
+    pub fn receive(&mut self, bytes: &[u8]) {
+        self.pipeline.receive(bytes)
+    }
+
+    pub fn next_frame(&mut self) -> Result<Option<PacketFrameBuf>, PacketReadableError> {
+        self.pipeline.next_frame()
+    }
+
+    /// Reads packet that can be received in the current state by this side.
+    pub fn read_packet<'a, P>(&self, frame: PacketFrame<'a>) -> Result<P, PacketReadableError>
+        where P: StatePacket<State, Side> + PacketReadable<'a> {
+        frame.read_packet()
+    }
+}
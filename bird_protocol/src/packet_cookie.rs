@@ -0,0 +1,60 @@
+use anyhow::Error;
+use bird_chat::identifier::Identifier;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Maximum length of the cookie payload that client stores.
+pub const MAX_COOKIE_LENGTH: usize = 5120;
+
+/// Length of the HMAC-SHA256 which is appended to the signed cookie.
+pub const COOKIE_MAC_LENGTH: usize = 32;
+
+/// Signs cookie payloads, so client can not forge cookies which are stored on it.
+///
+/// Signed cookie is payload followed by HMAC-SHA256 of the cookie key and payload,
+/// so cookie can not be moved under another key.
+/// Servers which are trusting each other (for example after transfer) should share the secret.
+#[derive(Clone)]
+pub struct CookieSigner {
+    mac: Hmac<Sha256>,
+}
+
+impl CookieSigner {
+    pub fn new(secret: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            mac: Hmac::new_from_slice(secret).map_err(|_| Error::msg("Bad secret length"))?,
+        })
+    }
+
+    fn mac(&self, key: &Identifier<'_>, payload: &[u8]) -> Hmac<Sha256> {
+        let mut mac = self.mac.clone();
+        mac.update(key.get_fulled().as_bytes());
+        // Identifier can not contain zero byte, so key and payload can not be mixed up
+        mac.update(&[0]);
+        mac.update(payload);
+        mac
+    }
+
+    /// Returns payload with the appended mac. Fails if the result is too big to be stored by client.
+    pub fn sign(&self, key: &Identifier<'_>, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        if payload.len() + COOKIE_MAC_LENGTH > MAX_COOKIE_LENGTH {
+            return Err(Error::msg("Cookie payload is too big"));
+        }
+        let mut cookie = Vec::with_capacity(payload.len() + COOKIE_MAC_LENGTH);
+        cookie.extend_from_slice(payload);
+        cookie.extend_from_slice(&self.mac(key, payload).finalize().into_bytes());
+        Ok(cookie)
+    }
+
+    /// Checks the mac of the signed cookie and returns its payload.
+    pub fn verify<'a>(&self, key: &Identifier<'_>, cookie: &'a [u8]) -> Result<&'a [u8], Error> {
+        if cookie.len() < COOKIE_MAC_LENGTH {
+            return Err(Error::msg("Cookie is not signed"));
+        }
+        let (payload, mac) = cookie.split_at(cookie.len() - COOKIE_MAC_LENGTH);
+        self.mac(key, payload)
+            .verify_slice(mac)
+            .map_err(|_| Error::msg("Bad cookie signature"))?;
+        Ok(payload)
+    }
+}
@@ -4,6 +4,12 @@ use bird_chat::identifier::Identifier;
 use uuid::Uuid;
 use crate::*;
 use crate::derive::*;
+use crate::packet_chat::{LastSeenUpdate, MESSAGE_SIGNATURE_LENGTH};
+use crate::packet_command::CommandGraph;
+#[cfg(feature = "fastnbt")]
+use crate::packet_metadata::EntityMetadata;
+#[cfg(feature = "fastnbt")]
+use crate::packet_slot::Slot;
 use serde::{Serialize, Deserialize};
 
 #[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq)]
@@ -12,13 +18,14 @@ use serde::{Serialize, Deserialize};
 pub enum HandshakeNextState {
     Status = 1,
     Login,
+    /// Login after transfer from another server, exists since 1.20.5.
+    Transfer,
 }
 
 #[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
 #[packet(bound = Server, state = Handshake, id = 0x00)]
 pub struct HandshakePacket<'a> {
-    #[variant(VarInt)]
-    pub protocol_version: i32,
+    pub protocol_version: ProtocolVersion,
     pub server_address: &'a str,
     pub server_port: u16,
     pub next_state: HandshakeNextState,
@@ -136,6 +143,12 @@ pub struct LoginPluginRequest<'a> {
     pub data: &'a [u8],
 }
 
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Login, id = { 766 => 0x05 })]
+pub struct LoginCookieRequest<'a> {
+    pub key: Identifier<'a>,
+}
+
 #[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
 pub struct LoginStartSignatureData<'a> {
     pub timestamp: i64,
@@ -184,6 +197,1431 @@ pub struct LoginPluginResponse<'a> {
     pub data: &'a [u8],
 }
 
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Login, id = { 764 => 0x03 })]
+pub struct LoginAcknowledged;
+
+type OptionBytesSliceVI<'a> = OptionVariant<LengthProvidedBytesSliceVI, &'a [u8]>;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Login, id = { 766 => 0x04 })]
+pub struct LoginCookieResponse<'a> {
+    pub key: Identifier<'a>,
+    /// [None] if cookie with such key is not stored.
+    #[variant(OptionBytesSliceVI)]
+    pub payload: Option<&'a [u8]>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum ChatMode {
+    Enabled = 0,
+    CommandsOnly,
+    Hidden,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum MainHand {
+    Left = 0,
+    Right,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum ParticleStatus {
+    All = 0,
+    Decreased,
+    Minimal,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ClientInformation<'a> {
+    pub locale: &'a str,
+    pub view_distance: i8,
+    pub chat_mode: ChatMode,
+    pub chat_colors: bool,
+    pub displayed_skin_parts: u8,
+    pub main_hand: MainHand,
+    pub enable_text_filtering: bool,
+    pub allow_server_listings: bool,
+    #[since(768)]
+    pub particle_status: Option<ParticleStatus>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x00, 766 => 0x01 })]
+pub struct ConfigurationClientboundPluginMessage<'a> {
+    pub channel: Identifier<'a>,
+    #[variant(RemainingBytesSlice)]
+    pub data: &'a [u8],
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x02, 766 => 0x03 })]
+pub struct ConfigurationFinish;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x03, 766 => 0x04 })]
+pub struct ConfigurationClientboundKeepAlive {
+    pub id: i64,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x04, 766 => 0x05 })]
+pub struct ConfigurationPing {
+    pub id: i32,
+}
+
+#[cfg(feature = "fastnbt")]
+type OptionNetworkNbt = OptionVariant<ProtocolNetworkNbt, fastnbt::Value>;
+
+#[cfg(feature = "fastnbt")]
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct RegistryDataEntry<'a> {
+    pub id: Identifier<'a>,
+    #[variant(OptionNetworkNbt)]
+    pub data: Option<fastnbt::Value>,
+}
+
+#[cfg(feature = "fastnbt")]
+type RegistryDataEntryArray<'a> = LengthProvidedSlice<VarInt, RegistryDataEntry<'a>, i32>;
+
+#[cfg(feature = "fastnbt")]
+/// Before 1.20.5 all registries are sent in one nbt codec,
+/// since 1.20.5 every registry is sent in its own packet.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x05, 766 => 0x07 })]
+pub struct ConfigurationRegistryData<'a> {
+    #[until(766)]
+    #[variant(ProtocolNetworkNbt)]
+    pub codec: Option<fastnbt::Value>,
+    #[since(766)]
+    pub registry_id: Option<Identifier<'a>>,
+    #[since(766)]
+    #[variant(RegistryDataEntryArray)]
+    pub entries: Option<Cow<'a, [RegistryDataEntry<'a>]>>,
+}
+
+type IdentifierArray<'a> = LengthProvidedSlice<VarInt, Identifier<'a>, i32>;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x07, 765 => 0x08, 766 => 0x0C })]
+pub struct ConfigurationFeatureFlags<'a> {
+    #[variant(IdentifierArray)]
+    pub feature_flags: Cow<'a, [Identifier<'a>]>,
+}
+
+type VarIntArray = LengthProvidedSlice<VarInt, VarInt, i32, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct Tag<'a> {
+    pub name: Identifier<'a>,
+    #[variant(VarIntArray)]
+    pub entries: Cow<'a, [i32]>,
+}
+
+type TagArray<'a> = LengthProvidedSlice<VarInt, Tag<'a>, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct TagRegistry<'a> {
+    pub registry: Identifier<'a>,
+    #[variant(TagArray)]
+    pub tags: Cow<'a, [Tag<'a>]>,
+}
+
+type TagRegistryArray<'a> = LengthProvidedSlice<VarInt, TagRegistry<'a>, i32>;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 764 => 0x08, 765 => 0x09, 766 => 0x0D })]
+pub struct ConfigurationUpdateTags<'a> {
+    #[variant(TagRegistryArray)]
+    pub registries: Cow<'a, [TagRegistry<'a>]>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct KnownPack<'a> {
+    pub namespace: &'a str,
+    pub id: &'a str,
+    pub version: &'a str,
+}
+
+type KnownPackArray<'a> = LengthProvidedSlice<VarInt, KnownPack<'a>, i32>;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 766 => 0x0E })]
+pub struct ConfigurationClientboundKnownPacks<'a> {
+    #[variant(KnownPackArray)]
+    pub packs: Cow<'a, [KnownPack<'a>]>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 766 => 0x00 })]
+pub struct ConfigurationCookieRequest<'a> {
+    pub key: Identifier<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 766 => 0x0A })]
+pub struct ConfigurationStoreCookie<'a> {
+    pub key: Identifier<'a>,
+    #[variant(LengthProvidedBytesSliceVI)]
+    pub payload: &'a [u8],
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Configuration, id = { 766 => 0x0B })]
+pub struct ConfigurationTransfer<'a> {
+    pub host: &'a str,
+    #[variant(VarInt)]
+    pub port: i32,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 764 => 0x00 })]
+pub struct ConfigurationClientInformation<'a> {
+    #[versioned]
+    pub information: ClientInformation<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 766 => 0x01 })]
+pub struct ConfigurationCookieResponse<'a> {
+    pub key: Identifier<'a>,
+    #[variant(OptionBytesSliceVI)]
+    pub payload: Option<&'a [u8]>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 764 => 0x01, 766 => 0x02 })]
+pub struct ConfigurationServerboundPluginMessage<'a> {
+    pub channel: Identifier<'a>,
+    #[variant(RemainingBytesSlice)]
+    pub data: &'a [u8],
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 764 => 0x02, 766 => 0x03 })]
+pub struct ConfigurationFinishAcknowledged;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 764 => 0x03, 766 => 0x04 })]
+pub struct ConfigurationServerboundKeepAlive {
+    pub id: i64,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 764 => 0x04, 766 => 0x05 })]
+pub struct ConfigurationPong {
+    pub id: i32,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Configuration, id = { 766 => 0x07 })]
+pub struct ConfigurationServerboundKnownPacks<'a> {
+    #[variant(KnownPackArray)]
+    pub packs: Cow<'a, [KnownPack<'a>]>,
+}
+
+/// Packets between two delimiters are handled by client in one tick.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x00, 767 => None })]
+pub struct PlayBundleDelimiter;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x19, 767 => None })]
+pub struct PlayClientboundPluginMessage<'a> {
+    pub channel: Identifier<'a>,
+    #[variant(RemainingBytesSlice)]
+    pub data: &'a [u8],
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x1D, 767 => None })]
+pub struct PlayDisconnect<'a> {
+    #[variant(ProtocolNbtComponent)]
+    pub reason: Component<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x26, 767 => None })]
+pub struct PlayClientboundKeepAlive {
+    pub id: i64,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x35, 767 => None })]
+pub struct PlayPing {
+    pub id: i32,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(u8)]
+pub enum GameMode {
+    Survival = 0,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i8)]
+pub enum PreviousGameMode {
+    None = -1,
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+/// Dimension type is sent as its name before 1.20.5 and as its registry id since.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DimensionTypeRef<'a> {
+    Name(Identifier<'a>),
+    Id(i32),
+}
+
+impl<'a> PacketVersionedReadable<'a> for DimensionTypeRef<'a> {
+    fn read_versioned<R>(read: &mut R, version: i32) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Ok(match version >= ProtocolVersion::V1_20_5.protocol() {
+            true => Self::Id(VarInt::read_variant(read)?),
+            false => Self::Name(Identifier::read(read)?),
+        })
+    }
+}
+
+impl PacketVersionedWritable for DimensionTypeRef<'_> {
+    fn write_versioned<W>(&self, write: &mut W, version: i32) -> Result<(), anyhow::Error> where W: PacketWrite {
+        match (self, version >= ProtocolVersion::V1_20_5.protocol()) {
+            (Self::Id(id), true) => VarInt::write_variant(id, write),
+            (Self::Name(name), false) => name.write(write),
+            _ => Err(anyhow::Error::msg("Dimension type can not be written in this protocol version")),
+        }
+    }
+}
+
+/// Registry of the registry codec, which is sent in join game packet before 1.20.2.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CodecRegistry<T> {
+    #[serde(rename = "type")]
+    pub registry_type: String,
+    pub value: Vec<CodecRegistryEntry<T>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CodecRegistryEntry<T> {
+    pub name: String,
+    pub id: i32,
+    pub element: T,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DimensionTypeElement {
+    pub piglin_safe: bool,
+    pub has_raids: bool,
+    /// Number or int provider compound.
+    pub monster_spawn_light_level: fastnbt::Value,
+    pub monster_spawn_block_light_limit: i32,
+    pub natural: bool,
+    pub ambient_light: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_time: Option<i64>,
+    pub infiniburn: String,
+    pub respawn_anchor_works: bool,
+    pub has_skylight: bool,
+    pub bed_works: bool,
+    pub effects: String,
+    pub min_y: i32,
+    pub height: i32,
+    pub logical_height: i32,
+    pub coordinate_scale: f64,
+    pub ultrawarm: bool,
+    pub has_ceiling: bool,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BiomeElement {
+    /// Removed in 1.19.4.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precipitation: Option<String>,
+    /// Added in 1.19.4.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_precipitation: Option<bool>,
+    pub temperature: f32,
+    pub downfall: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_modifier: Option<String>,
+    pub effects: fastnbt::Value,
+}
+
+#[cfg(feature = "fastnbt")]
+/// Registries which are sent in join game packet before 1.20.2.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegistryCodec {
+    #[serde(rename = "minecraft:dimension_type")]
+    pub dimension_type: CodecRegistry<DimensionTypeElement>,
+    #[serde(rename = "minecraft:worldgen/biome")]
+    pub biome: CodecRegistry<BiomeElement>,
+    #[serde(rename = "minecraft:chat_type")]
+    pub chat_type: CodecRegistry<fastnbt::Value>,
+    /// Added in 1.19.4.
+    #[serde(rename = "minecraft:damage_type", default, skip_serializing_if = "Option::is_none")]
+    pub damage_type: Option<CodecRegistry<fastnbt::Value>>,
+}
+
+#[cfg(feature = "fastnbt")]
+/// Join game packet before 1.20.2.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 759 => 0x23, 760 => 0x25, 761 => 0x24, 762 => 0x28, 764 => None })]
+pub struct PlayJoinGameLegacy<'a> {
+    pub entity_id: i32,
+    pub is_hardcore: bool,
+    pub game_mode: GameMode,
+    pub previous_game_mode: PreviousGameMode,
+    #[variant(IdentifierArray)]
+    pub dimension_names: Cow<'a, [Identifier<'a>]>,
+    #[variant(ProtocolNbt)]
+    pub registry_codec: RegistryCodec,
+    pub dimension_type: Identifier<'a>,
+    pub dimension_name: Identifier<'a>,
+    pub hashed_seed: i64,
+    #[variant(VarInt)]
+    pub max_players: i32,
+    #[variant(VarInt)]
+    pub view_distance: i32,
+    #[variant(VarInt)]
+    pub simulation_distance: i32,
+    pub reduced_debug_info: bool,
+    pub enable_respawn_screen: bool,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    pub death_location: Option<(Identifier<'a>, BlockPosition)>,
+    #[since(763)]
+    #[variant(VarInt)]
+    pub portal_cooldown: Option<i32>,
+}
+
+/// Respawn packet before 1.20.2.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 759 => 0x3B, 760 => 0x3E, 761 => 0x3D, 762 => 0x41, 764 => None })]
+pub struct PlayRespawnLegacy<'a> {
+    pub dimension_type: Identifier<'a>,
+    pub dimension_name: Identifier<'a>,
+    pub hashed_seed: i64,
+    pub game_mode: GameMode,
+    pub previous_game_mode: PreviousGameMode,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    pub data_kept: u8,
+    pub death_location: Option<(Identifier<'a>, BlockPosition)>,
+    #[since(763)]
+    #[variant(VarInt)]
+    pub portal_cooldown: Option<i32>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 764 => 0x29, 766 => 0x2B, 767 => None })]
+pub struct PlayJoinGame<'a> {
+    pub entity_id: i32,
+    pub is_hardcore: bool,
+    #[variant(IdentifierArray)]
+    pub dimension_names: Cow<'a, [Identifier<'a>]>,
+    #[variant(VarInt)]
+    pub max_players: i32,
+    #[variant(VarInt)]
+    pub view_distance: i32,
+    #[variant(VarInt)]
+    pub simulation_distance: i32,
+    pub reduced_debug_info: bool,
+    pub enable_respawn_screen: bool,
+    pub do_limited_crafting: bool,
+    #[versioned]
+    pub dimension_type: DimensionTypeRef<'a>,
+    pub dimension_name: Identifier<'a>,
+    pub hashed_seed: i64,
+    pub game_mode: GameMode,
+    pub previous_game_mode: PreviousGameMode,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    pub death_location: Option<(Identifier<'a>, BlockPosition)>,
+    #[variant(VarInt)]
+    pub portal_cooldown: i32,
+    #[since(766)]
+    pub enforces_secure_chat: Option<bool>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 764 => 0x43, 765 => 0x45, 766 => 0x47, 767 => None })]
+pub struct PlayRespawn<'a> {
+    #[versioned]
+    pub dimension_type: DimensionTypeRef<'a>,
+    pub dimension_name: Identifier<'a>,
+    pub hashed_seed: i64,
+    pub game_mode: GameMode,
+    pub previous_game_mode: PreviousGameMode,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    pub death_location: Option<(Identifier<'a>, BlockPosition)>,
+    #[variant(VarInt)]
+    pub portal_cooldown: i32,
+    /// Bit mask, 0x01 keeps attributes and 0x02 keeps metadata.
+    pub data_kept: u8,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x16, 767 => None })]
+pub struct PlayCookieRequest<'a> {
+    pub key: Identifier<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x6B, 767 => None })]
+pub struct PlayStoreCookie<'a> {
+    pub key: Identifier<'a>,
+    #[variant(LengthProvidedBytesSliceVI)]
+    pub payload: &'a [u8],
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x73, 767 => None })]
+pub struct PlayTransfer<'a> {
+    pub host: &'a str,
+    #[variant(VarInt)]
+    pub port: i32,
+}
+
+/// Bit mask of [PlaySynchronizePlayerPosition] fields which are relative to the current position and rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RelativeFlags(pub u8);
+
+impl RelativeFlags {
+    pub const X: Self = Self(0x01);
+    pub const Y: Self = Self(0x02);
+    pub const Z: Self = Self(0x04);
+    pub const Y_ROT: Self = Self(0x08);
+    pub const X_ROT: Self = Self(0x10);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(self, flags: Self) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    pub const fn with(self, flags: Self) -> Self {
+        Self(self.0 | flags.0)
+    }
+}
+
+impl<'a> PacketReadable<'a> for RelativeFlags {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Ok(Self(u8::read(read)?))
+    }
+}
+
+impl PacketWritable for RelativeFlags {
+    fn write<W>(&self, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
+        self.0.write(write)
+    }
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x40, 767 => None })]
+pub struct PlaySynchronizePlayerPosition {
+    pub position: EntityPosition,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub flags: RelativeFlags,
+    /// Client confirms the teleport with [PlayConfirmTeleportation] with the same id.
+    #[variant(VarInt)]
+    pub teleport_id: i32,
+}
+
+impl PlaySynchronizePlayerPosition {
+    /// Applies the packet to the current position and rotation (yaw, pitch) of the player.
+    pub fn resolve(&self, position: EntityPosition, rotation: (f32, f32)) -> (EntityPosition, (f32, f32)) {
+        let relative = |flag, value: f64, current: f64| match self.flags.contains(flag) {
+            true => current + value,
+            false => value,
+        };
+        (
+            EntityPosition {
+                x: relative(RelativeFlags::X, self.position.x, position.x),
+                y: relative(RelativeFlags::Y, self.position.y, position.y),
+                z: relative(RelativeFlags::Z, self.position.z, position.z),
+            },
+            (
+                relative(RelativeFlags::Y_ROT, self.yaw as f64, rotation.0 as f64) as f32,
+                relative(RelativeFlags::X_ROT, self.pitch as f64, rotation.1 as f64) as f32,
+            ),
+        )
+    }
+}
+
+/// Entity movement of at most 8 blocks on each axis. Written as three [i16] of 1/4096 block.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EntityDelta {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl EntityDelta {
+    /// Returns [None] if entity is moved by 8 blocks or more and [PlayTeleportEntity] should be sent instead.
+    pub fn between(from: EntityPosition, to: EntityPosition) -> Option<Self> {
+        let delta = |from: f64, to: f64| i16::try_from(
+            (to * 4096.0).round() as i64 - (from * 4096.0).round() as i64
+        ).ok();
+        Some(Self {
+            x: delta(from.x, to.x)?,
+            y: delta(from.y, to.y)?,
+            z: delta(from.z, to.z)?,
+        })
+    }
+
+    /// Returns movement in blocks.
+    pub fn to_blocks(self) -> (f64, f64, f64) {
+        (self.x as f64 / 4096.0, self.y as f64 / 4096.0, self.z as f64 / 4096.0)
+    }
+
+    pub fn apply(self, position: EntityPosition) -> EntityPosition {
+        let (x, y, z) = self.to_blocks();
+        EntityPosition { x: position.x + x, y: position.y + y, z: position.z + z }
+    }
+}
+
+/// Entity velocity. Written as three [i16] of 1/8000 block per tick.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EntityVelocity {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl EntityVelocity {
+    /// Velocity is clamped to the protocol limit of 3.9 blocks per tick.
+    pub fn from_blocks_per_tick(x: f64, y: f64, z: f64) -> Self {
+        let velocity = |value: f64| (value.clamp(-3.9, 3.9) * 8000.0).round() as i16;
+        Self { x: velocity(x), y: velocity(y), z: velocity(z) }
+    }
+
+    pub fn to_blocks_per_tick(self) -> (f64, f64, f64) {
+        (self.x as f64 / 8000.0, self.y as f64 / 8000.0, self.z as f64 / 8000.0)
+    }
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x01, 767 => None })]
+pub struct PlaySpawnEntity {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub entity_uuid: Uuid,
+    /// Id in the `minecraft:entity_type` registry.
+    #[variant(VarInt)]
+    pub entity_type: i32,
+    pub position: EntityPosition,
+    #[variant(Angle)]
+    pub pitch: f32,
+    #[variant(Angle)]
+    pub yaw: f32,
+    #[variant(Angle)]
+    pub head_yaw: f32,
+    /// Meaning depends on the entity type, for example block state of the falling block.
+    #[variant(VarInt)]
+    pub data: i32,
+    pub velocity: EntityVelocity,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x02, 767 => None })]
+pub struct PlaySpawnExperienceOrb {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub position: EntityPosition,
+    pub count: i16,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x2E, 767 => None })]
+pub struct PlayUpdateEntityPosition {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub delta: EntityDelta,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x2F, 767 => None })]
+pub struct PlayUpdateEntityPositionAndRotation {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub delta: EntityDelta,
+    #[variant(Angle)]
+    pub yaw: f32,
+    #[variant(Angle)]
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x30, 767 => None })]
+pub struct PlayUpdateEntityRotation {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    #[variant(Angle)]
+    pub yaw: f32,
+    #[variant(Angle)]
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x48, 767 => None })]
+pub struct PlaySetHeadRotation {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    #[variant(Angle)]
+    pub head_yaw: f32,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x70, 767 => None })]
+pub struct PlayTeleportEntity {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub position: EntityPosition,
+    #[variant(Angle)]
+    pub yaw: f32,
+    #[variant(Angle)]
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x5A, 767 => None })]
+pub struct PlaySetEntityVelocity {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub velocity: EntityVelocity,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x42, 767 => None })]
+pub struct PlayRemoveEntities<'a> {
+    #[variant(VarIntArray)]
+    pub entity_ids: Cow<'a, [i32]>,
+}
+
+#[cfg(feature = "fastnbt")]
+/// Heightmaps of the chunk, each is 256 entries packed into longs by `ceil(log2(height + 1))` bits.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Heightmaps {
+    #[serde(rename = "MOTION_BLOCKING", default, skip_serializing_if = "Option::is_none")]
+    pub motion_blocking: Option<fastnbt::LongArray>,
+    #[serde(rename = "WORLD_SURFACE", default, skip_serializing_if = "Option::is_none")]
+    pub world_surface: Option<fastnbt::LongArray>,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ChunkBlockEntity {
+    /// Section relative x and z, `x << 4 | z`.
+    pub packed_xz: u8,
+    pub y: i16,
+    /// Id in the `minecraft:block_entity_type` registry.
+    #[variant(VarInt)]
+    pub block_entity_type: i32,
+    #[variant(ProtocolOptionalNetworkNbt)]
+    pub data: Option<fastnbt::Value>,
+}
+
+#[cfg(feature = "fastnbt")]
+impl ChunkBlockEntity {
+    pub fn x(&self) -> u8 {
+        self.packed_xz >> 4
+    }
+
+    pub fn z(&self) -> u8 {
+        self.packed_xz & 0x0F
+    }
+}
+
+type LongArray = LengthProvidedSlice<VarInt, i64, i32>;
+#[cfg(feature = "fastnbt")]
+type ChunkBlockEntityArray = LengthProvidedSlice<VarInt, ChunkBlockEntity, i32>;
+type LightArray<'a> = LengthProvidedSlice<VarInt, LengthProvidedBytesSliceVI, i32, &'a [u8]>;
+
+/// Light of the chunk. Masks are bit sets where bit is set for each section which light is sent,
+/// the lowest bit is the section below the world. Each light array is 2048 bytes, half byte per block.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct LightData<'a> {
+    #[variant(LongArray)]
+    pub sky_light_mask: Cow<'a, [i64]>,
+    #[variant(LongArray)]
+    pub block_light_mask: Cow<'a, [i64]>,
+    #[variant(LongArray)]
+    pub empty_sky_light_mask: Cow<'a, [i64]>,
+    #[variant(LongArray)]
+    pub empty_block_light_mask: Cow<'a, [i64]>,
+    #[variant(LightArray)]
+    pub sky_light: Cow<'a, [&'a [u8]]>,
+    #[variant(LightArray)]
+    pub block_light: Cow<'a, [&'a [u8]]>,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x27, 767 => None })]
+pub struct PlayChunkDataAndUpdateLight<'a> {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    /// Heightmaps are sent as network nbt.
+    #[variant(ProtocolNetworkNbt)]
+    pub heightmaps: Heightmaps,
+    /// Chunk sections from the bottom to the top, see [ChunkSection::read_all] and [ChunkSection::write_all].
+    #[variant(LengthProvidedBytesSliceVI)]
+    pub data: &'a [u8],
+    #[variant(ChunkBlockEntityArray)]
+    pub block_entities: Cow<'a, [ChunkBlockEntity]>,
+    pub light: LightData<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x2A, 767 => None })]
+pub struct PlayUpdateLight<'a> {
+    #[variant(VarInt)]
+    pub chunk_x: i32,
+    #[variant(VarInt)]
+    pub chunk_z: i32,
+    pub light: LightData<'a>,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x58, 767 => None })]
+pub struct PlaySetEntityMetadata<'a> {
+    #[variant(VarInt)]
+    pub entity_id: i32,
+    pub metadata: EntityMetadata<'a>,
+}
+
+/// Type of the screen, id in the `minecraft:menu` registry.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum ScreenType {
+    Generic9x1 = 0,
+    Generic9x2,
+    Generic9x3,
+    Generic9x4,
+    Generic9x5,
+    Generic9x6,
+    Generic3x3,
+    Crafter3x3,
+    Anvil,
+    Beacon,
+    BlastFurnace,
+    BrewingStand,
+    Crafting,
+    Enchantment,
+    Furnace,
+    Grindstone,
+    Hopper,
+    Lectern,
+    Loom,
+    Merchant,
+    ShulkerBox,
+    Smithing,
+    Smoker,
+    CartographyTable,
+    Stonecutter,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum ClickMode {
+    /// Left or right click.
+    Pickup = 0,
+    /// Shift click.
+    QuickMove,
+    /// Number key or offhand swap, button is the hotbar slot or 40 for offhand.
+    Swap,
+    /// Middle click in creative mode.
+    Clone,
+    /// Drop key.
+    Throw,
+    /// Dragging, button defines the stage and the mouse button.
+    QuickCraft,
+    /// Double click.
+    PickupAll,
+}
+
+#[cfg(feature = "fastnbt")]
+type SlotArray<'a> = LengthProvidedSlice<VarInt, Slot<'a>, i32>;
+
+#[cfg(feature = "fastnbt")]
+/// Maximum count of the changed slots in [PlayClickContainer], more slots are rejected by the vanilla server.
+pub const MAX_CHANGED_SLOTS: usize = 128;
+
+#[cfg(feature = "fastnbt")]
+struct ChangedSlotArray;
+
+#[cfg(feature = "fastnbt")]
+impl<'a> PacketVariantReadable<'a, Cow<'a, [(i16, Slot<'a>)]>> for ChangedSlotArray {
+    fn read_variant<R>(read: &mut R) -> Result<Cow<'a, [(i16, Slot<'a>)]>, PacketReadableError>
+        where R: PacketRead<'a> {
+        let length: i32 = VarInt::read_variant(read)?;
+        if length < 0 || length as usize > MAX_CHANGED_SLOTS {
+            return Err(PacketReadableError::Any(anyhow::anyhow!("Too many changed slots: {}", length)));
+        }
+        let mut slots = Vec::with_capacity(length as usize);
+        for _ in 0..length {
+            slots.push(<(i16, Slot<'a>)>::read(read)?);
+        }
+        Ok(Cow::Owned(slots))
+    }
+}
+
+#[cfg(feature = "fastnbt")]
+impl PacketVariantWritable<Cow<'_, [(i16, Slot<'_>)]>> for ChangedSlotArray {
+    fn write_variant<W>(object: &Cow<[(i16, Slot)]>, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
+        if object.len() > MAX_CHANGED_SLOTS {
+            return Err(anyhow::anyhow!("Too many changed slots: {}", object.len()));
+        }
+        LengthProvidedSlice::<VarInt, (i16, Slot), i32>::write_variant(object, write)
+    }
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x33, 767 => None })]
+pub struct PlayOpenScreen<'a> {
+    #[variant(VarInt)]
+    pub window_id: i32,
+    pub window_type: ScreenType,
+    #[variant(ProtocolNbtComponent)]
+    pub title: Component<'a>,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x13, 767 => None })]
+pub struct PlaySetContainerContent<'a> {
+    /// Window id is 0 for the player inventory.
+    pub window_id: u8,
+    /// State id, which client sends back in [PlayClickContainer].
+    #[variant(VarInt)]
+    pub state_id: i32,
+    #[variant(SlotArray)]
+    pub slots: Cow<'a, [Slot<'a>]>,
+    /// Item which is held by the cursor.
+    pub carried_item: Slot<'a>,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x15, 767 => None })]
+pub struct PlaySetContainerSlot<'a> {
+    /// Window id is -1 and slot is -1 to set the carried item.
+    pub window_id: i8,
+    #[variant(VarInt)]
+    pub state_id: i32,
+    pub slot: i16,
+    pub slot_data: Slot<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x14, 767 => None })]
+pub struct PlayContainerProperty {
+    pub window_id: u8,
+    /// Meaning depends on the screen type, for example furnace fuel left.
+    pub property: i16,
+    pub value: i16,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x12, 767 => None })]
+pub struct PlayClientboundCloseContainer {
+    pub window_id: u8,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x53, 767 => None })]
+pub struct PlayClientboundSetHeldItem {
+    /// Hotbar slot from 0 to 8.
+    pub slot: i8,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x0E, 767 => None })]
+pub struct PlayClickContainer<'a> {
+    pub window_id: u8,
+    /// Last state id which client received.
+    #[variant(VarInt)]
+    pub state_id: i32,
+    /// Clicked slot, -999 if click is outside of the window.
+    pub slot: i16,
+    pub button: i8,
+    pub mode: ClickMode,
+    /// Slots which are changed by the click, how client predicts them.
+    #[variant(ChangedSlotArray)]
+    pub changed_slots: Cow<'a, [(i16, Slot<'a>)]>,
+    pub carried_item: Slot<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x0F, 767 => None })]
+pub struct PlayServerboundCloseContainer {
+    pub window_id: u8,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x2F, 767 => None })]
+pub struct PlayServerboundSetHeldItem {
+    pub slot: i16,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x32, 767 => None })]
+pub struct PlaySetCreativeModeSlot<'a> {
+    /// Slot of the player inventory, -1 to drop the item.
+    pub slot: i16,
+    pub clicked_item: Slot<'a>,
+}
+
+#[cfg(feature = "fastnbt")]
+type OptionNbtComponent<'a> = OptionVariant<ProtocolNbtComponent, Component<'a>>;
+
+/// Signature of the previous message, which is sent as its id in the client cache if it is cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackedMessageSignature<'a> {
+    Id(i32),
+    Signature(&'a [u8; MESSAGE_SIGNATURE_LENGTH]),
+}
+
+impl<'a> PacketReadable<'a> for PackedMessageSignature<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        // Id is written increased by one and zero means that signature is following
+        Ok(match VarInt::read_variant(read)? {
+            0 => Self::Signature(<&'a [u8; MESSAGE_SIGNATURE_LENGTH]>::read(read)?),
+            id => Self::Id(id - 1),
+        })
+    }
+}
+
+impl PacketWritable for PackedMessageSignature<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
+        match self {
+            Self::Id(id) => VarInt::write_variant(&(id + 1), write),
+            Self::Signature(signature) => {
+                VarInt::write_variant(&0, write)?;
+                signature.write(write)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "fastnbt")]
+type PackedMessageSignatureArray<'a> = LengthProvidedSlice<VarInt, PackedMessageSignature<'a>, i32>;
+
+/// Which parts of the message are hidden by the server chat filter.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum FilterMask<'a> {
+    PassThrough,
+    FullyFiltered,
+    /// Bit set of the filtered characters.
+    PartiallyFiltered(#[variant(LongArray)] Cow<'a, [i64]>),
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x39, 767 => None })]
+pub struct PlayPlayerChat<'a> {
+    pub sender: Uuid,
+    /// Index of the message in the sender chain.
+    #[variant(VarInt)]
+    pub index: i32,
+    pub message_signature: Option<&'a [u8; MESSAGE_SIGNATURE_LENGTH]>,
+    pub message: &'a str,
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Last seen messages of the sender.
+    #[variant(PackedMessageSignatureArray)]
+    pub previous_messages: Cow<'a, [PackedMessageSignature<'a>]>,
+    /// Content which is shown instead of the signed message, for example after server side formatting.
+    #[variant(OptionNbtComponent)]
+    pub unsigned_content: Option<Component<'a>>,
+    pub filter: FilterMask<'a>,
+    /// Id in the `minecraft:chat_type` registry.
+    #[variant(VarInt)]
+    pub chat_type: i32,
+    #[variant(ProtocolNbtComponent)]
+    pub sender_name: Component<'a>,
+    #[variant(OptionNbtComponent)]
+    pub target_name: Option<Component<'a>>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x1C, 767 => None })]
+pub struct PlayDeleteMessage<'a> {
+    pub signature: PackedMessageSignature<'a>,
+}
+
+#[cfg(feature = "fastnbt")]
+/// Chat message which is sent without a signature, but shown as the player chat message.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x1E, 767 => None })]
+pub struct PlayDisguisedChat<'a> {
+    #[variant(ProtocolNbtComponent)]
+    pub message: Component<'a>,
+    #[variant(VarInt)]
+    pub chat_type: i32,
+    #[variant(ProtocolNbtComponent)]
+    pub sender_name: Component<'a>,
+    #[variant(OptionNbtComponent)]
+    pub target_name: Option<Component<'a>>,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x6C, 767 => None })]
+pub struct PlaySystemChat<'a> {
+    #[variant(ProtocolNbtComponent)]
+    pub content: Component<'a>,
+    /// Message is shown above the hotbar instead of the chat.
+    pub overlay: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x03, 767 => None })]
+pub struct PlayMessageAcknowledgment {
+    /// Offset of the last seen messages, see [LastSeenUpdate].
+    #[variant(VarInt)]
+    pub message_count: i32,
+}
+
+/// Command without signed arguments.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x04, 767 => None })]
+pub struct PlayChatCommand<'a> {
+    /// Command without the leading slash.
+    pub command: &'a str,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ArgumentSignature<'a> {
+    pub argument_name: &'a str,
+    pub signature: &'a [u8; MESSAGE_SIGNATURE_LENGTH],
+}
+
+type ArgumentSignatureArray<'a> = LengthProvidedSlice<VarInt, ArgumentSignature<'a>, i32>;
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x05, 767 => None })]
+pub struct PlaySignedChatCommand<'a> {
+    pub command: &'a str,
+    pub timestamp: i64,
+    pub salt: i64,
+    /// Signatures of the message arguments, for example in `/msg`.
+    #[variant(ArgumentSignatureArray)]
+    pub argument_signatures: Cow<'a, [ArgumentSignature<'a>]>,
+    pub last_seen: LastSeenUpdate,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x06, 767 => None })]
+pub struct PlayChatMessage<'a> {
+    pub message: &'a str,
+    pub timestamp: i64,
+    pub salt: i64,
+    pub signature: Option<&'a [u8; MESSAGE_SIGNATURE_LENGTH]>,
+    pub last_seen: LastSeenUpdate,
+}
+
+/// Chat session of the player, messages are signed by the session key since 1.19.3.
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x07, 767 => None })]
+pub struct PlayPlayerSession<'a> {
+    pub session_id: Uuid,
+    /// Milliseconds since the unix epoch.
+    pub expires_at: i64,
+    /// Der encoded RSA public key.
+    #[variant(LengthProvidedBytesSliceVI)]
+    pub public_key: &'a [u8],
+    /// Signature of the public key by Mojang.
+    #[variant(LengthProvidedBytesSliceVI)]
+    pub key_signature: &'a [u8],
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x11, 767 => None })]
+pub struct PlayCookieResponse<'a> {
+    pub key: Identifier<'a>,
+    #[variant(OptionBytesSliceVI)]
+    pub payload: Option<&'a [u8]>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x12, 767 => None })]
+pub struct PlayServerboundPluginMessage<'a> {
+    pub channel: Identifier<'a>,
+    #[variant(RemainingBytesSlice)]
+    pub data: &'a [u8],
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x18, 767 => None })]
+pub struct PlayServerboundKeepAlive {
+    pub id: i64,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x27, 767 => None })]
+pub struct PlayPong {
+    pub id: i32,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x00, 767 => None })]
+pub struct PlayConfirmTeleportation {
+    #[variant(VarInt)]
+    pub teleport_id: i32,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x1A, 767 => None })]
+pub struct PlaySetPlayerPosition {
+    /// Position of the player feet.
+    pub position: EntityPosition,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x1B, 767 => None })]
+pub struct PlaySetPlayerPositionAndRotation {
+    pub position: EntityPosition,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x1C, 767 => None })]
+pub struct PlaySetPlayerRotation {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x1D, 767 => None })]
+pub struct PlaySetPlayerOnGround {
+    pub on_ground: bool,
+}
+
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Server, state = Handshake)]
+pub enum ServerboundHandshake<'a> {
+    Handshake(HandshakePacket<'a>),
+}
+
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Client, state = Status)]
+pub enum ClientboundStatus<'a> {
+    Response(StatusResponse<'a>),
+    PingResponse(StatusPingResponse),
+}
+
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Server, state = Status)]
+pub enum ServerboundStatus {
+    Request(StatusRequest),
+    PingRequest(StatusPingRequest),
+}
+
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Client, state = Login)]
+pub enum ClientboundLogin<'a> {
+    Disconnect(LoginDisconnect<'a>),
+    EncryptionRequest(LoginEncryptionRequest<'a>),
+    Success(LoginSuccess<'a>),
+    SetCompression(LoginSetCompression),
+    PluginRequest(LoginPluginRequest<'a>),
+    CookieRequest(LoginCookieRequest<'a>),
+}
+
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Server, state = Login)]
+pub enum ServerboundLogin<'a> {
+    Start(LoginStart<'a>),
+    EncryptionResponse(LoginEncryptionResponse<'a>),
+    PluginResponse(LoginPluginResponse<'a>),
+    Acknowledged(LoginAcknowledged),
+    CookieResponse(LoginCookieResponse<'a>),
+}
+
+/// Configuration packets of the latest protocol version.
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Client, state = Configuration)]
+pub enum ClientboundConfiguration<'a> {
+    PluginMessage(ConfigurationClientboundPluginMessage<'a>),
+    Finish(ConfigurationFinish),
+    KeepAlive(ConfigurationClientboundKeepAlive),
+    Ping(ConfigurationPing),
+    #[cfg(feature = "fastnbt")]
+    RegistryData(ConfigurationRegistryData<'a>),
+    FeatureFlags(ConfigurationFeatureFlags<'a>),
+    UpdateTags(ConfigurationUpdateTags<'a>),
+    KnownPacks(ConfigurationClientboundKnownPacks<'a>),
+    CookieRequest(ConfigurationCookieRequest<'a>),
+    StoreCookie(ConfigurationStoreCookie<'a>),
+    Transfer(ConfigurationTransfer<'a>),
+}
+
+/// Configuration packets of the latest protocol version.
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Server, state = Configuration)]
+pub enum ServerboundConfiguration<'a> {
+    ClientInformation(ConfigurationClientInformation<'a>),
+    PluginMessage(ConfigurationServerboundPluginMessage<'a>),
+    FinishAcknowledged(ConfigurationFinishAcknowledged),
+    KeepAlive(ConfigurationServerboundKeepAlive),
+    Pong(ConfigurationPong),
+    KnownPacks(ConfigurationServerboundKnownPacks<'a>),
+    CookieResponse(ConfigurationCookieResponse<'a>),
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x11, 767 => None })]
+pub struct PlayCommands<'a> {
+    pub graph: CommandGraph<'a>,
+}
+
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Server, state = Play, id = { 766 => 0x0B, 767 => None })]
+pub struct PlayCommandSuggestionsRequest<'a> {
+    #[variant(VarInt)]
+    pub transaction_id: i32,
+    /// Text which is typed by the player, including the slash.
+    pub text: &'a str,
+}
+
+#[cfg(feature = "fastnbt")]
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct CommandSuggestion<'a> {
+    pub text: &'a str,
+    #[variant(OptionNbtComponent)]
+    pub tooltip: Option<Component<'a>>,
+}
+
+#[cfg(feature = "fastnbt")]
+type CommandSuggestionArray<'a> = LengthProvidedSlice<VarInt, CommandSuggestion<'a>, i32>;
+
+#[cfg(feature = "fastnbt")]
+#[derive(Packet, PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[packet(bound = Client, state = Play, id = { 766 => 0x10, 767 => None })]
+pub struct PlayCommandSuggestionsResponse<'a> {
+    #[variant(VarInt)]
+    pub transaction_id: i32,
+    /// Start of the replaced text, in characters of the request text.
+    #[variant(VarInt)]
+    pub start: i32,
+    #[variant(VarInt)]
+    pub length: i32,
+    #[variant(CommandSuggestionArray)]
+    pub matches: Cow<'a, [CommandSuggestion<'a>]>,
+}
+
+/// Play packets of 1.20.5 and 1.20.6 (766).
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Client, state = Play)]
+pub enum ClientboundPlay<'a> {
+    BundleDelimiter(PlayBundleDelimiter),
+    PluginMessage(PlayClientboundPluginMessage<'a>),
+    #[cfg(feature = "fastnbt")]
+    Disconnect(PlayDisconnect<'a>),
+    KeepAlive(PlayClientboundKeepAlive),
+    Ping(PlayPing),
+    JoinGame(PlayJoinGame<'a>),
+    Respawn(PlayRespawn<'a>),
+    CookieRequest(PlayCookieRequest<'a>),
+    StoreCookie(PlayStoreCookie<'a>),
+    Transfer(PlayTransfer<'a>),
+    SynchronizePlayerPosition(PlaySynchronizePlayerPosition),
+    SpawnEntity(PlaySpawnEntity),
+    SpawnExperienceOrb(PlaySpawnExperienceOrb),
+    UpdateEntityPosition(PlayUpdateEntityPosition),
+    UpdateEntityPositionAndRotation(PlayUpdateEntityPositionAndRotation),
+    UpdateEntityRotation(PlayUpdateEntityRotation),
+    SetHeadRotation(PlaySetHeadRotation),
+    TeleportEntity(PlayTeleportEntity),
+    SetEntityVelocity(PlaySetEntityVelocity),
+    RemoveEntities(PlayRemoveEntities<'a>),
+    #[cfg(feature = "fastnbt")]
+    SetEntityMetadata(PlaySetEntityMetadata<'a>),
+    #[cfg(feature = "fastnbt")]
+    ChunkDataAndUpdateLight(PlayChunkDataAndUpdateLight<'a>),
+    UpdateLight(PlayUpdateLight<'a>),
+    #[cfg(feature = "fastnbt")]
+    OpenScreen(PlayOpenScreen<'a>),
+    #[cfg(feature = "fastnbt")]
+    SetContainerContent(PlaySetContainerContent<'a>),
+    #[cfg(feature = "fastnbt")]
+    SetContainerSlot(PlaySetContainerSlot<'a>),
+    ContainerProperty(PlayContainerProperty),
+    CloseContainer(PlayClientboundCloseContainer),
+    SetHeldItem(PlayClientboundSetHeldItem),
+    #[cfg(feature = "fastnbt")]
+    PlayerChat(PlayPlayerChat<'a>),
+    DeleteMessage(PlayDeleteMessage<'a>),
+    #[cfg(feature = "fastnbt")]
+    DisguisedChat(PlayDisguisedChat<'a>),
+    #[cfg(feature = "fastnbt")]
+    SystemChat(PlaySystemChat<'a>),
+    Commands(PlayCommands<'a>),
+    #[cfg(feature = "fastnbt")]
+    CommandSuggestionsResponse(PlayCommandSuggestionsResponse<'a>),
+}
+
+/// Play packets of 1.20.5 and 1.20.6 (766).
+#[derive(PacketSet, Debug, Clone, PartialEq)]
+#[packet_set(bound = Server, state = Play)]
+pub enum ServerboundPlay<'a> {
+    CookieResponse(PlayCookieResponse<'a>),
+    PluginMessage(PlayServerboundPluginMessage<'a>),
+    KeepAlive(PlayServerboundKeepAlive),
+    Pong(PlayPong),
+    ConfirmTeleportation(PlayConfirmTeleportation),
+    SetPlayerPosition(PlaySetPlayerPosition),
+    SetPlayerPositionAndRotation(PlaySetPlayerPositionAndRotation),
+    SetPlayerRotation(PlaySetPlayerRotation),
+    SetPlayerOnGround(PlaySetPlayerOnGround),
+    #[cfg(feature = "fastnbt")]
+    ClickContainer(PlayClickContainer<'a>),
+    CloseContainer(PlayServerboundCloseContainer),
+    SetHeldItem(PlayServerboundSetHeldItem),
+    #[cfg(feature = "fastnbt")]
+    SetCreativeModeSlot(PlaySetCreativeModeSlot<'a>),
+    MessageAcknowledgment(PlayMessageAcknowledgment),
+    ChatCommand(PlayChatCommand<'a>),
+    SignedChatCommand(PlaySignedChatCommand<'a>),
+    ChatMessage(PlayChatMessage<'a>),
+    PlayerSession(PlayPlayerSession<'a>),
+    CommandSuggestionsRequest(PlayCommandSuggestionsRequest<'a>),
+}
+
 fn is_cow_empty<T: Clone>(cow: &Cow<[T]>) -> bool {
     cow.is_empty()
 }
\ No newline at end of file
@@ -0,0 +1,71 @@
+use aes::Aes128;
+use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::typenum::U1;
+use anyhow::Error;
+use crate::packet::PacketWrite;
+
+type Encryptor = cfb8::Encryptor<Aes128>;
+type Decryptor = cfb8::Decryptor<Aes128>;
+
+/// AES-128 CFB8 stream cipher that is used after login encryption.
+/// Shared secret is used as key and also as initial vector.
+pub struct PacketCipher {
+    encryptor: Encryptor,
+    decryptor: Decryptor,
+}
+
+/// [PacketWrite] that encrypts all bytes before writing them to the inner [PacketWrite].
+pub struct CipherPacketWrite<'c, W: PacketWrite> {
+    pub cipher: &'c mut PacketCipher,
+    pub write: W,
+}
+
+impl PacketCipher {
+    pub fn new(shared_secret: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            encryptor: Encryptor::new_from_slices(shared_secret, shared_secret)
+                .map_err(|_| Error::msg("Bad shared secret length"))?,
+            decryptor: Decryptor::new_from_slices(shared_secret, shared_secret)
+                .map_err(|_| Error::msg("Bad shared secret length"))?,
+        })
+    }
+
+    pub fn encrypt(&mut self, bytes: &mut [u8]) {
+        self.encryptor.encrypt_blocks_mut(as_blocks(bytes))
+    }
+
+    pub fn decrypt(&mut self, bytes: &mut [u8]) {
+        self.decryptor.decrypt_blocks_mut(as_blocks(bytes))
+    }
+
+    pub fn writer<W: PacketWrite>(&mut self, write: W) -> CipherPacketWrite<'_, W> {
+        CipherPacketWrite { cipher: self, write }
+    }
+}
+
+fn as_blocks(bytes: &mut [u8]) -> &mut [GenericArray<u8, U1>] {
+    // Safety. GenericArray<u8, U1> has the same layout as [u8; 1],
+    // so slice of bytes can be used as slice of one byte blocks
+    unsafe { std::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut GenericArray<u8, U1>, bytes.len()) }
+}
+
+impl<W: PacketWrite> PacketWrite for CipherPacketWrite<'_, W> {
+    fn write_byte(&mut self, byte: u8) -> Result<(), Error> {
+        self.write_bytes_fixed([byte])
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.write_bytes_owned(bytes.to_vec())
+    }
+
+    fn write_bytes_owned(&mut self, mut bytes: Vec<u8>) -> Result<(), Error> {
+        self.cipher.encrypt(bytes.as_mut_slice());
+        self.write.write_bytes_owned(bytes)
+    }
+
+    fn write_bytes_fixed<const SIZE: usize>(&mut self, mut bytes: [u8; SIZE]) -> Result<(), Error> {
+        self.cipher.encrypt(bytes.as_mut_slice());
+        self.write.write_bytes_fixed(bytes)
+    }
+}
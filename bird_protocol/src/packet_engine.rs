@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+use anyhow::Error;
+use crate::packet::{PacketBound, PacketReadableError, PacketState, PacketVersionedWritable};
+use crate::packet_compression::PacketCompression;
+use crate::packet::VersionedPacket;
+use crate::packet_default::{ConfigurationFinishAcknowledged, HandshakeNextState, HandshakePacket, LoginAcknowledged, LoginSetCompression, LoginSuccess};
+use crate::packet_frame::{PacketFrame, PacketFrameBuf, packet_data_versioned};
+use crate::packet_pipeline::PacketPipeline;
+use crate::packet_set::{PacketSet, PacketSetError};
+use crate::packet_version::ProtocolVersion;
+
+/// Change of the connection that is made by [ProtocolEngine].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    StateChanged(PacketState),
+    CompressionEnabled(PacketCompression),
+    CompressionDisabled,
+    EncryptionEnabled,
+}
+
+/// Protocol engine that does not do any io.
+///
+/// Received bytes are given to [ProtocolEngine::receive] and packets are taken by [ProtocolEngine::poll_frame].
+/// Sent packets are queued by [ProtocolEngine::send] and bytes to be sent are taken by [ProtocolEngine::poll_transmit].
+/// Engine tracks handshake, compression, login success and configuration packets and changes the connection by itself,
+/// every such change is reported by [ProtocolEngine::poll_event].
+pub struct ProtocolEngine {
+    bound: PacketBound,
+    state: PacketState,
+    version: ProtocolVersion,
+    pipeline: PacketPipeline,
+    transmit: Vec<u8>,
+    events: VecDeque<EngineEvent>,
+}
+
+impl ProtocolEngine {
+    /// Creates engine that receives packets with the given bound.
+    pub fn new(bound: PacketBound) -> Self {
+        Self {
+            bound,
+            state: PacketState::Handshake,
+            version: ProtocolVersion::LATEST,
+            pipeline: PacketPipeline::new(),
+            transmit: Vec::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn client() -> Self {
+        Self::new(PacketBound::Client)
+    }
+
+    pub fn server() -> Self {
+        Self::new(PacketBound::Server)
+    }
+
+    /// Bound of packets that are received by this engine.
+    pub fn bound(&self) -> PacketBound {
+        self.bound
+    }
+
+    pub fn state(&self) -> PacketState {
+        self.state
+    }
+
+    /// Protocol version from the handshake packet. It is the latest version until handshake is made.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    pub fn pipeline(&self) -> &PacketPipeline {
+        &self.pipeline
+    }
+
+    pub fn receive(&mut self, bytes: &[u8]) {
+        self.pipeline.receive(bytes)
+    }
+
+    /// Takes next received packet. Returns [None] if more bytes are needed.
+    pub fn poll_frame(&mut self) -> Result<Option<PacketFrameBuf>, PacketReadableError> {
+        let frame = match self.pipeline.next_frame()? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        self.track(frame.as_frame(), self.bound)?;
+        Ok(Some(frame))
+    }
+
+    /// Reads packet of the set with the id and layout of the protocol version.
+    pub fn read_packet<'a, S>(&self, frame: PacketFrame<'a>) -> Result<S, PacketSetError<'a>> where S: PacketSet<'a> {
+        S::read_packet_versioned(frame, self.version.protocol())
+    }
+
+    /// Queues packet to be sent with the id and layout of the protocol version.
+    /// Fails if packet can not be sent in the current state by this side or does not exist in the protocol version.
+    pub fn send<T>(&mut self, packet: &T) -> Result<(), Error> where T: VersionedPacket + PacketVersionedWritable {
+        if T::state() != self.state || T::bound() == self.bound {
+            return Err(Error::msg("Packet can not be sent in the current state"));
+        }
+        let data = packet_data_versioned(packet, self.version.protocol())?;
+        self.send_data(&data)
+    }
+
+    /// Queues already read packet to be sent.
+    pub fn send_frame(&mut self, frame: PacketFrame<'_>) -> Result<(), Error> {
+        let mut data = Vec::with_capacity(frame.body.len() + 5);
+        frame.write_data(&mut data)?;
+        self.send_data(&data)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.pipeline.write_data(data, &mut self.transmit)?;
+        // Changes must be applied after the packet is written
+        let frame = PacketFrame::new(data)?;
+        self.track(frame, self.bound.opposite())?;
+        Ok(())
+    }
+
+    /// Takes bytes that should be sent.
+    pub fn poll_transmit(&mut self) -> Option<Vec<u8>> {
+        match self.transmit.is_empty() {
+            true => None,
+            false => Some(std::mem::take(&mut self.transmit)),
+        }
+    }
+
+    pub fn poll_event(&mut self) -> Option<EngineEvent> {
+        self.events.pop_front()
+    }
+
+    /// Enables encryption with the given shared secret.
+    /// Must be called after encryption response packet is received by the server or sent by the client.
+    #[cfg(feature = "encryption")]
+    pub fn enable_encryption(&mut self, shared_secret: &[u8]) -> Result<(), Error> {
+        self.pipeline.enable_encryption(crate::packet_encryption::PacketCipher::new(shared_secret)?);
+        self.events.push_back(EngineEvent::EncryptionEnabled);
+        Ok(())
+    }
+
+    fn track(&mut self, frame: PacketFrame<'_>, bound: PacketBound) -> Result<(), PacketReadableError> {
+        match (self.state, bound, frame.id) {
+            (PacketState::Handshake, HandshakePacket::BOUND, HandshakePacket::ID) => {
+                let handshake: HandshakePacket = frame.read_packet()?;
+                self.version = handshake.protocol_version;
+                self.change_state(match handshake.next_state {
+                    HandshakeNextState::Status => PacketState::Status,
+                    HandshakeNextState::Login | HandshakeNextState::Transfer => PacketState::Login,
+                });
+            }
+            (PacketState::Login, LoginSetCompression::BOUND, LoginSetCompression::ID) => {
+                let set_compression: LoginSetCompression = frame.read_packet()?;
+                let compression = PacketCompression::from_packet(&set_compression);
+                self.pipeline.set_compression(compression);
+                self.events.push_back(match compression {
+                    Some(compression) => EngineEvent::CompressionEnabled(compression),
+                    None => EngineEvent::CompressionDisabled,
+                });
+            }
+            (PacketState::Login, LoginSuccess::BOUND, LoginSuccess::ID)
+            if !self.version.has_configuration_state() => self.change_state(PacketState::Play),
+            (PacketState::Login, LoginAcknowledged::BOUND, id)
+            if Some(id) == self.id_for::<LoginAcknowledged>() => self.change_state(PacketState::Configuration),
+            (PacketState::Configuration, ConfigurationFinishAcknowledged::BOUND, id)
+            if Some(id) == self.id_for::<ConfigurationFinishAcknowledged>() => self.change_state(PacketState::Play),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn id_for<T: VersionedPacket>(&self) -> Option<i32> {
+        match self.version.has_configuration_state() {
+            true => T::id_for(self.version.protocol()),
+            false => None,
+        }
+    }
+
+    fn change_state(&mut self, state: PacketState) {
+        self.state = state;
+        self.events.push_back(EngineEvent::StateChanged(state));
+    }
+}
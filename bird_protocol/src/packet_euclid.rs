@@ -1,7 +1,7 @@
 use anyhow::Error;
 use euclid::default::Vector3D;
 use crate::packet::{PacketRead, PacketReadable, PacketReadableError, PacketVariantReadable, PacketVariantWritable, PacketWritable, PacketWrite};
-use crate::packet_types::{Angle, BlockPosition};
+use crate::packet_types::{Angle, BlockPosition, EntityPosition};
 
 impl<'a> PacketReadable<'a> for euclid::Angle<f32> {
     fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
@@ -45,6 +45,31 @@ impl PacketVariantWritable<euclid::default::Vector3D<i32>> for BlockPosition {
     }
 }
 
+impl<'a> PacketVariantReadable<'a, euclid::default::Vector3D<f64>> for EntityPosition {
+    fn read_variant<R>(read: &mut R) -> Result<euclid::default::Vector3D<f64>, PacketReadableError>
+        where R: PacketRead<'a> {
+        EntityPosition::read(read).map(|position| position.into())
+    }
+}
+
+impl PacketVariantWritable<euclid::default::Vector3D<f64>> for EntityPosition {
+    fn write_variant<W>(object: &Vector3D<f64>, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        EntityPosition::from(*object).write(write)
+    }
+}
+
+impl From<EntityPosition> for Vector3D<f64> {
+    fn from(position: EntityPosition) -> Self {
+        euclid::Vector3D::new(position.x, position.y, position.z)
+    }
+}
+
+impl From<Vector3D<f64>> for EntityPosition {
+    fn from(vector: Vector3D<f64>) -> Self {
+        EntityPosition { x: vector.x, y: vector.y, z: vector.z }
+    }
+}
+
 macro_rules! angle_variant_impl {
     ($inner: ty) => {
         impl<'a> PacketVariantReadable<'a, euclid::Angle<$inner>> for Angle {
@@ -1,8 +1,9 @@
 use std::marker::PhantomData;
 use anyhow::Error;
 use serde::Deserialize;
-use crate::packet::{PacketRead, PacketReadable, PacketReadableError, PacketVariantReadable, PacketVariantWritable, PacketWrite};
-use crate::packet_types::{ProtocolNbt, RemainingBytesSlice};
+use serde::de::DeserializeOwned;
+use crate::packet::{PacketRead, PacketReadable, PacketReadableError, PacketVariantReadable, PacketVariantWritable, PacketWritable, PacketWrite};
+use crate::packet_types::{ProtocolNbt, ProtocolNbtComponent, ProtocolNetworkNbt, ProtocolOptionalNetworkNbt, RemainingBytesSlice};
 
 impl<'a, T: serde::Serialize> PacketVariantWritable<T> for ProtocolNbt {
     fn write_variant<W>(object: &T, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
@@ -10,6 +11,9 @@ impl<'a, T: serde::Serialize> PacketVariantWritable<T> for ProtocolNbt {
     }
 }
 
+/// Maximum depth of the nested compounds and lists, deeper nbt is rejected like by the vanilla nbt reader.
+pub const MAX_NBT_DEPTH: usize = 512;
+
 struct MemorizePacketRead<'b, 'a, R: PacketRead<'a>> {
     pub length: usize,
     pub input: &'b mut R,
@@ -18,8 +22,8 @@ struct MemorizePacketRead<'b, 'a, R: PacketRead<'a>> {
 
 impl<'b, 'a, R: PacketRead<'a>> MemorizePacketRead<'b, 'a, R> {
     fn skip_bytes(&mut self, size: usize) -> Result<(), anyhow::Error> {
+        // Length is counted by take_slice
         self.take_slice(size)?;
-        self.length += size;
         Ok(())
     }
 }
@@ -51,20 +55,30 @@ impl<'b, 'a, R: PacketRead<'a>> PacketRead<'a> for MemorizePacketRead<'b, 'a, R>
     }
 }
 
-fn read_nbt_length<'a, R>(input: &mut R) -> Result<usize, PacketReadableError> where R: PacketRead<'a> {
+fn read_nbt_length<'a, R>(input: &mut R, named: bool) -> Result<usize, PacketReadableError> where R: PacketRead<'a> {
     let mut memorize = MemorizePacketRead { input, length: 0, a_ph: PhantomData };
     let tag = u8::read(&mut memorize)?;
     if tag != 0 {
-        let length = u16::read(&mut memorize)?;
-        memorize.skip_bytes(length as usize)?;
-        skip_tag(tag, 1, &mut memorize)?
+        if named {
+            let length = u16::read(&mut memorize)?;
+            memorize.skip_bytes(length as usize)?;
+        }
+        skip_tag(tag, 1, &mut memorize, 0)?
     }
     memorize.input.rollback(memorize.length)?;
     Ok(memorize.length)
 }
 
+fn nested_depth(depth: usize) -> Result<usize, PacketReadableError> {
+    match depth < MAX_NBT_DEPTH {
+        true => Ok(depth + 1),
+        false => Err(PacketReadableError::Any(anyhow::anyhow!("Nbt is deeper than {}", MAX_NBT_DEPTH))),
+    }
+}
+
+/// Skips tags of the given depth, which is the count of compounds and lists they are inside of.
 fn skip_tag<'a, R: PacketRead<'a>>(
-    tag: u8, count: usize, input: &mut MemorizePacketRead<'_, 'a, R>,
+    tag: u8, count: usize, input: &mut MemorizePacketRead<'_, 'a, R>, depth: usize,
 ) -> Result<(), PacketReadableError> {
     Ok(match tag {
         0 => (),
@@ -72,6 +86,18 @@ fn skip_tag<'a, R: PacketRead<'a>>(
         2 => input.skip_bytes(count * 2)?,
         3 | 5 => input.skip_bytes(count * 4)?,
         4 | 6 => input.skip_bytes(count * 8)?,
+        // Elements of these tags have different sizes, so each element of the list is skipped by itself
+        7..=12 => for _ in 0..count {
+            skip_sized_tag(tag, input, depth)?
+        }
+        _ => Err(anyhow::Error::msg("Bad nbt tag value"))?
+    })
+}
+
+fn skip_sized_tag<'a, R: PacketRead<'a>>(
+    tag: u8, input: &mut MemorizePacketRead<'_, 'a, R>, depth: usize,
+) -> Result<(), PacketReadableError> {
+    Ok(match tag {
         7 => {
             let length = i32::read(input)?;
             if length <= 0 { return Ok(()); }
@@ -82,17 +108,21 @@ fn skip_tag<'a, R: PacketRead<'a>>(
             input.skip_bytes(length as usize)?
         }
         9 => {
+            let depth = nested_depth(depth)?;
             let tag = u8::read(input)?;
             let size = i32::read(input)?;
             if size <= 0 { return Ok(()); }
-            skip_tag(tag, size as usize, input)?
+            skip_tag(tag, size as usize, input, depth)?
         }
-        10 => loop {
-            let tag = u8::read(input)?;
-            if tag == 0 { break (); }
-            let name_length = u16::read(input)?;
-            input.skip_bytes(name_length as usize)?;
-            skip_tag(tag, 1, input)?
+        10 => {
+            let depth = nested_depth(depth)?;
+            loop {
+                let tag = u8::read(input)?;
+                if tag == 0 { break; }
+                let name_length = u16::read(input)?;
+                input.skip_bytes(name_length as usize)?;
+                skip_tag(tag, 1, input, depth)?
+            }
         }
         11 => {
             let length = i32::read(input)?;
@@ -104,14 +134,166 @@ fn skip_tag<'a, R: PacketRead<'a>>(
             if length <= 0 { return Ok(()); }
             input.skip_bytes(length as usize * 8)?
         }
-        _ => Err(anyhow::Error::msg("Bad nbt tag value"))?
+        _ => unreachable!(),
     })
 }
 
 impl<'a, T: Deserialize<'a>> PacketVariantReadable<'a, T> for ProtocolNbt {
     fn read_variant<R>(read: &mut R) -> Result<T, PacketReadableError> where R: PacketRead<'a> {
-        let length = read_nbt_length(read)?;
+        let length = read_nbt_length(read, true)?;
         fastnbt::from_bytes(read.take_slice(length)?)
             .map_err(|err| PacketReadableError::Any(err.into()))
     }
-}
\ No newline at end of file
+}
+
+impl<T: serde::Serialize> PacketVariantWritable<T> for ProtocolNetworkNbt {
+    fn write_variant<W>(object: &T, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
+        let bytes = fastnbt::to_bytes(object)?;
+        // Root name is removed, it is written after the tag as u16 length and string
+        let name_length = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        write.write_byte(bytes[0])?;
+        write.write_bytes(&bytes[3 + name_length..])
+    }
+}
+
+impl<'a, T: DeserializeOwned> PacketVariantReadable<'a, T> for ProtocolNetworkNbt {
+    fn read_variant<R>(read: &mut R) -> Result<T, PacketReadableError> where R: PacketRead<'a> {
+        let length = read_nbt_length(read, false)?;
+        let slice = read.take_slice(length)?;
+        if slice[0] == 0 {
+            return Err(PacketReadableError::Any(anyhow::Error::msg("Nbt is empty")));
+        }
+        // fastnbt reads only named nbt, so empty root name is inserted
+        let mut bytes = Vec::with_capacity(slice.len() + 2);
+        bytes.push(slice[0]);
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(&slice[1..]);
+        fastnbt::from_bytes(&bytes)
+            .map_err(|err| PacketReadableError::Any(err.into()))
+    }
+}
+
+impl<T: serde::Serialize> PacketVariantWritable<Option<T>> for ProtocolOptionalNetworkNbt {
+    fn write_variant<W>(object: &Option<T>, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
+        match object {
+            Some(object) => ProtocolNetworkNbt::write_variant(object, write),
+            None => write.write_byte(0),
+        }
+    }
+}
+
+impl<'a, T: DeserializeOwned> PacketVariantReadable<'a, Option<T>> for ProtocolOptionalNetworkNbt {
+    fn read_variant<R>(read: &mut R) -> Result<Option<T>, PacketReadableError> where R: PacketRead<'a> {
+        if read.take_byte()? == 0 {
+            return Ok(None);
+        }
+        read.rollback(1)?;
+        ProtocolNetworkNbt::read_variant(read).map(Some)
+    }
+}
+
+const STRING_TAG: u8 = 8;
+
+fn json_to_nbt(value: serde_json::Value) -> Result<fastnbt::Value, Error> {
+    use serde_json::Value;
+    Ok(match value {
+        Value::Null => return Err(Error::msg("Null can not be written as nbt")),
+        Value::Bool(value) => fastnbt::Value::Byte(value as i8),
+        Value::Number(number) => match (number.as_i64(), number.as_f64()) {
+            (Some(value), _) => match i32::try_from(value) {
+                Ok(value) => fastnbt::Value::Int(value),
+                Err(_) => fastnbt::Value::Long(value),
+            },
+            (None, Some(value)) => fastnbt::Value::Double(value),
+            (None, None) => return Err(Error::msg("Number can not be written as nbt")),
+        },
+        Value::String(value) => fastnbt::Value::String(value),
+        Value::Array(values) => fastnbt::Value::List(
+            values.into_iter().map(json_to_nbt).collect::<Result<_, _>>()?
+        ),
+        Value::Object(values) => fastnbt::Value::Compound(
+            values.into_iter()
+                .map(|(key, value)| Ok((key, json_to_nbt(value)?)))
+                .collect::<Result<_, Error>>()?
+        ),
+    })
+}
+
+fn nbt_to_json(value: fastnbt::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        // Bytes of text components are boolean flags
+        fastnbt::Value::Byte(value) => Value::Bool(value != 0),
+        fastnbt::Value::Short(value) => value.into(),
+        fastnbt::Value::Int(value) => value.into(),
+        fastnbt::Value::Long(value) => value.into(),
+        fastnbt::Value::Float(value) => value.into(),
+        fastnbt::Value::Double(value) => value.into(),
+        fastnbt::Value::String(value) => value.into(),
+        fastnbt::Value::ByteArray(values) => values.iter().map(|value| Value::from(*value)).collect(),
+        fastnbt::Value::IntArray(values) => values.iter().map(|value| Value::from(*value)).collect(),
+        fastnbt::Value::LongArray(values) => values.iter().map(|value| Value::from(*value)).collect(),
+        fastnbt::Value::List(values) => values.into_iter().map(nbt_to_json).collect(),
+        fastnbt::Value::Compound(values) => Value::Object(
+            values.into_iter().map(|(key, value)| (key, nbt_to_json(value))).collect()
+        ),
+    }
+}
+
+/// Empty extra is not written, but bird-chat components can not be deserialized without it.
+/// Plain text children are written as strings, or as compounds with the empty key if list is mixed.
+fn fill_component_extra(value: &mut serde_json::Value) {
+    if let serde_json::Value::Object(ref mut object) = value {
+        if object.len() == 1 {
+            if let Some(inner) = object.remove("") {
+                *value = inner;
+            }
+        }
+    }
+    if let serde_json::Value::String(ref text) = value {
+        *value = serde_json::json!({ "text": text });
+    }
+    if let serde_json::Value::Object(ref mut object) = value {
+        object.entry("extra").or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        for key in ["extra", "with"] {
+            if let Some(serde_json::Value::Array(ref mut children)) = object.get_mut(key) {
+                children.iter_mut().for_each(fill_component_extra);
+            }
+        }
+    }
+}
+
+impl<T: serde::Serialize> PacketVariantWritable<T> for ProtocolNbtComponent {
+    fn write_variant<W>(object: &T, write: &mut W) -> Result<(), anyhow::Error> where W: PacketWrite {
+        match json_to_nbt(serde_json::to_value(object)?)? {
+            // Plain text is written as string tag
+            fastnbt::Value::String(text) => {
+                let length = u16::try_from(text.len()).map_err(|_| Error::msg("Too big string"))?;
+                write.write_byte(STRING_TAG)?;
+                length.write(write)?;
+                write.write_bytes(text.as_bytes())
+            }
+            value => ProtocolNetworkNbt::write_variant(&value, write),
+        }
+    }
+}
+
+impl<'a, T: DeserializeOwned> PacketVariantReadable<'a, T> for ProtocolNbtComponent {
+    fn read_variant<R>(read: &mut R) -> Result<T, PacketReadableError> where R: PacketRead<'a> {
+        let mut value = match u8::read(read)? {
+            STRING_TAG => {
+                let length = u16::read(read)?;
+                let text = std::str::from_utf8(read.take_slice(length as usize)?)
+                    .map_err(|err| PacketReadableError::Any(err.into()))?;
+                serde_json::json!({ "text": text })
+            }
+            _ => {
+                read.rollback(1)?;
+                let value: fastnbt::Value = ProtocolNetworkNbt::read_variant(read)?;
+                nbt_to_json(value)
+            }
+        };
+        fill_component_extra(&mut value);
+        serde_json::from_value(value).map_err(|err| PacketReadableError::Any(err.into()))
+    }
+}
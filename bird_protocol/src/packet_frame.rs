@@ -0,0 +1,234 @@
+use std::iter::FusedIterator;
+use anyhow::Error;
+use crate::packet::{Packet, PacketRead, PacketReadable, PacketReadableError, PacketVariantReadable, PacketVariantWritable, PacketVersionedReadable, PacketVersionedWritable, PacketWritable, PacketWrite, SlicePacketRead, VersionedPacket};
+use crate::packet_types::{PacketVariant, VarInt};
+
+/// Maximum length of frame that can be sent by vanilla. Length prefix of such frame takes 3 bytes.
+pub const MAX_FRAME_LENGTH: usize = 2097151;
+
+/// Result of reading frame from the start of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameRead<'a> {
+    /// Frame is complete. `consumed` is the count of bytes that frame takes with its length prefix.
+    Frame {
+        frame: &'a [u8],
+        consumed: usize,
+    },
+    /// Buffer doesn't contain the whole frame yet.
+    NeedMore,
+}
+
+/// Packet id and body of the frame that is not read yet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacketFrame<'a> {
+    pub id: i32,
+    pub body: &'a [u8],
+}
+
+/// Owned version of [PacketFrame].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketFrameBuf {
+    pub id: i32,
+    pub body: Vec<u8>,
+}
+
+/// Iterator over complete frames of the buffer.
+///
+/// Iteration stops on the first incomplete frame or after the first error, [Frames::consumed] returns
+/// the count of bytes that can be dropped from the buffer after iteration.
+pub struct Frames<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    failed: bool,
+}
+
+/// Reads frame from the start of the buffer.
+pub fn read_frame(bytes: &[u8]) -> Result<FrameRead<'_>, PacketReadableError> {
+    let mut read = SlicePacketRead::new(bytes);
+    let length = match <VarInt as PacketVariantReadable<i32>>::read_variant(&mut read) {
+        Ok(length) => length,
+        Err(PacketReadableError::BytesExceeded) => return Ok(FrameRead::NeedMore),
+        Err(err) => return Err(err),
+    };
+    if length < 0 || length as usize > MAX_FRAME_LENGTH {
+        return Err(PacketReadableError::Any(Error::msg("Bad frame length")));
+    }
+    let prefix_length = bytes.len() - read.available();
+    Ok(match read.take_slice(length as usize) {
+        Ok(frame) => FrameRead::Frame { frame, consumed: prefix_length + length as usize },
+        Err(_) => FrameRead::NeedMore,
+    })
+}
+
+/// Writes length prefix and then frame itself.
+pub fn write_frame<W>(frame: &[u8], write: &mut W) -> Result<(), Error> where W: PacketWrite {
+    match frame.len() > MAX_FRAME_LENGTH {
+        true => Err(Error::msg("Too big frame")),
+        false => {
+            VarInt::write_variant(&(frame.len() as i32), write)?;
+            write.write_bytes(frame)
+        }
+    }
+}
+
+/// Writes packet with its id and length prefix.
+pub fn write_packet_frame<T, W>(packet: &T, write: &mut W) -> Result<(), Error>
+    where T: Packet + PacketWritable, W: PacketWrite {
+    write_frame(&packet_data(packet)?, write)
+}
+
+/// Returns packet id and packet itself as bytes. This is what is placed inside the frame.
+pub fn packet_data<T>(packet: &T) -> Result<Vec<u8>, Error> where T: Packet + PacketWritable {
+    let mut data = Vec::new();
+    PacketVariant::write_variant(packet, &mut data)?;
+    Ok(data)
+}
+
+/// Writes packet with its id and layout of the protocol version and length prefix.
+pub fn write_packet_frame_versioned<T, W>(packet: &T, write: &mut W, version: i32) -> Result<(), Error>
+    where T: VersionedPacket + PacketVersionedWritable, W: PacketWrite {
+    write_frame(&packet_data_versioned(packet, version)?, write)
+}
+
+/// Returns packet id and packet itself as bytes of the protocol version.
+/// Fails if packet does not exist in this version.
+pub fn packet_data_versioned<T>(packet: &T, version: i32) -> Result<Vec<u8>, Error>
+    where T: VersionedPacket + PacketVersionedWritable {
+    let id = T::id_for(version)
+        .ok_or_else(|| anyhow::anyhow!("Packet does not exist in protocol version {}", version))?;
+    let mut data = Vec::new();
+    VarInt::write_variant(&id, &mut data)?;
+    packet.write_versioned(&mut data, version)?;
+    Ok(data)
+}
+
+impl<'a> PacketFrame<'a> {
+    /// Splits frame into packet id and body.
+    pub fn new(frame: &'a [u8]) -> Result<Self, PacketReadableError> {
+        let mut read = SlicePacketRead::new(frame);
+        let id = VarInt::read_variant(&mut read)?;
+        Ok(Self { id, body: read.take_slice(read.available())? })
+    }
+
+    /// Reads packet from the body. Fails if id of the frame is not id of the packet
+    /// or packet does not take the whole body.
+    pub fn read_packet<T>(&self) -> Result<T, PacketReadableError> where T: Packet + PacketReadable<'a> {
+        if self.id != T::id() {
+            return Err(PacketReadableError::Any(Error::msg("Bad packet id")));
+        }
+        self.read_body(T::read)
+    }
+
+    /// Reads packet with the layout of the protocol version. Fails if id of the frame is not id
+    /// of the packet in this version or packet does not take the whole body.
+    pub fn read_packet_versioned<T>(&self, version: i32) -> Result<T, PacketReadableError>
+        where T: VersionedPacket + PacketVersionedReadable<'a> {
+        if Some(self.id) != T::id_for(version) {
+            return Err(PacketReadableError::Any(Error::msg("Bad packet id")));
+        }
+        self.read_body(|read| T::read_versioned(read, version))
+    }
+
+    fn read_body<T>(
+        &self, read_fn: impl FnOnce(&mut SlicePacketRead<'a>) -> Result<T, PacketReadableError>,
+    ) -> Result<T, PacketReadableError> {
+        let mut read = SlicePacketRead::new(self.body);
+        let packet = read_fn(&mut read)?;
+        match read.available() {
+            0 => Ok(packet),
+            _ => Err(PacketReadableError::Any(Error::msg("Packet is not fully read"))),
+        }
+    }
+
+    /// Writes packet id and body without length prefix.
+    pub fn write_data<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.id, write)?;
+        write.write_bytes(self.body)
+    }
+
+    pub fn to_buf(&self) -> PacketFrameBuf {
+        PacketFrameBuf { id: self.id, body: self.body.to_vec() }
+    }
+}
+
+impl PacketFrameBuf {
+    pub fn new(frame: &[u8]) -> Result<Self, PacketReadableError> {
+        PacketFrame::new(frame).map(|frame| frame.to_buf())
+    }
+
+    pub fn as_frame(&self) -> PacketFrame<'_> {
+        PacketFrame { id: self.id, body: self.body.as_slice() }
+    }
+
+    pub fn read_packet<'a, T>(&'a self) -> Result<T, PacketReadableError> where T: Packet + PacketReadable<'a> {
+        self.as_frame().read_packet()
+    }
+
+    pub fn read_packet_versioned<'a, T>(&'a self, version: i32) -> Result<T, PacketReadableError>
+        where T: VersionedPacket + PacketVersionedReadable<'a> {
+        self.as_frame().read_packet_versioned(version)
+    }
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0, failed: false }
+    }
+
+    /// Count of bytes that are taken by returned frames.
+    pub fn consumed(&self) -> usize {
+        self.offset
+    }
+
+    /// Bytes that are not returned as frames yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<&'a [u8], PacketReadableError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        match read_frame(self.remaining()) {
+            Ok(FrameRead::Frame { frame, consumed }) => {
+                self.offset += consumed;
+                Some(Ok(frame))
+            }
+            Ok(FrameRead::NeedMore) => None,
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl FusedIterator for Frames<'_> {}
+
+impl<'a> PacketReadable<'a> for PacketFrame<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let length = VarInt::read_variant(read)?;
+        if length < 0 || length as usize > MAX_FRAME_LENGTH {
+            return Err(PacketReadableError::Any(Error::msg("Bad frame length")));
+        }
+        PacketFrame::new(read.take_slice(length as usize)?)
+    }
+}
+
+impl PacketWritable for PacketFrame<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        let mut data = Vec::with_capacity(self.body.len() + 5);
+        self.write_data(&mut data)?;
+        write_frame(&data, write)
+    }
+}
+
+impl PacketWritable for PacketFrameBuf {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        self.as_frame().write(write)
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use anyhow::Error;
+use crate::packet::{Packet, PacketWritable, PacketWrite};
+use crate::packet_frame::{PacketFrame, PacketFrameBuf};
+use crate::packet_pipeline::PacketPipeline;
+
+const DEFAULT_CAPACITY: usize = 8192;
+
+/// [PacketWrite] for any [std::io::Write].
+pub struct IoPacketWrite<W: Write>(pub W);
+
+/// Reads frames from [std::io::Read].
+pub struct PacketReader<R: Read> {
+    read: R,
+    buffer: Box<[u8]>,
+    pipeline: PacketPipeline,
+}
+
+/// Writes packets to [std::io::Write]. Written bytes are buffered until [PacketWriter::flush].
+pub struct PacketWriter<W: Write> {
+    write: IoPacketWrite<BufWriter<W>>,
+    pipeline: PacketPipeline,
+}
+
+impl<W: Write> PacketWrite for IoPacketWrite<W> {
+    fn write_byte(&mut self, byte: u8) -> Result<(), Error> {
+        self.write_bytes(&[byte])
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        Ok(self.0.write_all(bytes)?)
+    }
+
+    fn write_bytes_owned(&mut self, bytes: Vec<u8>) -> Result<(), Error> {
+        self.write_bytes(bytes.as_slice())
+    }
+
+    fn write_bytes_fixed<const SIZE: usize>(&mut self, bytes: [u8; SIZE]) -> Result<(), Error> {
+        self.write_bytes(bytes.as_slice())
+    }
+}
+
+impl<R: Read> PacketReader<R> {
+    pub fn new(read: R) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, read)
+    }
+
+    /// Creates reader that reads at most `capacity` bytes from [std::io::Read] at once.
+    /// Zero capacity is treated as one, since empty read means the end of the stream.
+    pub fn with_capacity(capacity: usize, read: R) -> Self {
+        Self {
+            read,
+            buffer: vec![0; capacity.max(1)].into_boxed_slice(),
+            pipeline: PacketPipeline::new(),
+        }
+    }
+
+    pub fn pipeline(&self) -> &PacketPipeline {
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut PacketPipeline {
+        &mut self.pipeline
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.read
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.read
+    }
+
+    /// Blocks until the whole frame is read.
+    pub fn read_frame(&mut self) -> Result<PacketFrameBuf, Error> {
+        loop {
+            if let Some(frame) = self.pipeline.next_frame()? {
+                break Ok(frame);
+            }
+            let length = match self.read.read(&mut self.buffer) {
+                Ok(0) => return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(length) => length,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            self.pipeline.receive(&self.buffer[..length]);
+        }
+    }
+}
+
+impl<W: Write> PacketWriter<W> {
+    pub fn new(write: W) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, write)
+    }
+
+    pub fn with_capacity(capacity: usize, write: W) -> Self {
+        Self {
+            write: IoPacketWrite(BufWriter::with_capacity(capacity, write)),
+            pipeline: PacketPipeline::new(),
+        }
+    }
+
+    pub fn pipeline(&self) -> &PacketPipeline {
+        &self.pipeline
+    }
+
+    pub fn pipeline_mut(&mut self) -> &mut PacketPipeline {
+        &mut self.pipeline
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.write.0.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        self.write.0.get_mut()
+    }
+
+    pub fn write_packet<T>(&mut self, packet: &T) -> Result<(), Error> where T: Packet + PacketWritable {
+        self.pipeline.write_packet(packet, &mut self.write)
+    }
+
+    pub fn write_frame(&mut self, frame: PacketFrame<'_>) -> Result<(), Error> {
+        let mut data = Vec::with_capacity(frame.body.len() + 5);
+        frame.write_data(&mut data)?;
+        self.pipeline.write_data(&data, &mut self.write)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.write.0.flush()?)
+    }
+}
@@ -0,0 +1,418 @@
+use anyhow::Error;
+use bird_chat::component::Component;
+use bird_chat::identifier::Identifier;
+use uuid::Uuid;
+use crate::*;
+use crate::derive::*;
+use crate::packet_slot::Slot;
+
+type OptionComponent<'a> = OptionVariant<ProtocolNbtComponent, Component<'a>>;
+
+/// Index which ends the entity metadata.
+pub const ENTITY_METADATA_END: u8 = 0xFF;
+
+/// Entity metadata, entries are written one by one and ended by [ENTITY_METADATA_END].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EntityMetadata<'a> {
+    pub entries: Vec<EntityMetadataEntry<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityMetadataEntry<'a> {
+    pub index: u8,
+    pub value: EntityMetadataValue<'a>,
+}
+
+/// Value of the entity metadata entry. Serializer ids are the ids of 1.20.5.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityMetadataValue<'a> {
+    Byte(i8),
+    VarInt(i32),
+    VarLong(i64),
+    Float(f32),
+    String(&'a str),
+    Component(Component<'a>),
+    OptionalComponent(Option<Component<'a>>),
+    Slot(Slot<'a>),
+    Boolean(bool),
+    /// Rotation on x, y and z axes in degrees.
+    Rotations(f32, f32, f32),
+    Position(BlockPosition),
+    OptionalPosition(Option<BlockPosition>),
+    Direction(Direction),
+    OptionalUuid(Option<Uuid>),
+    BlockState(i32),
+    /// Air is written as absent block state.
+    OptionalBlockState(Option<i32>),
+    Nbt(fastnbt::Value),
+    Particle(Particle<'a>),
+    Particles(Vec<Particle<'a>>),
+    VillagerData(VillagerData),
+    OptionalVarInt(Option<i32>),
+    Pose(Pose),
+    CatVariant(i32),
+    WolfVariant(i32),
+    FrogVariant(i32),
+    /// Dimension and position in it.
+    OptionalGlobalPosition(Option<(Identifier<'a>, BlockPosition)>),
+    PaintingVariant(i32),
+    SnifferState(SnifferState),
+    ArmadilloState(ArmadilloState),
+    Vector3(f32, f32, f32),
+    Quaternion(f32, f32, f32, f32),
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum Direction {
+    Down = 0,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum Pose {
+    Standing = 0,
+    FallFlying,
+    Sleeping,
+    Swimming,
+    SpinAttack,
+    Sneaking,
+    LongJumping,
+    Dying,
+    Croaking,
+    UsingTongue,
+    Sitting,
+    Roaring,
+    Sniffing,
+    Emerging,
+    Digging,
+    Sliding,
+    Shooting,
+    Inhaling,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum SnifferState {
+    Idling = 0,
+    FeelingHappy,
+    Scenting,
+    Sniffing,
+    Searching,
+    Digging,
+    Rising,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum ArmadilloState {
+    Idle = 0,
+    Rolling,
+    Scared,
+    Unrolling,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VillagerData {
+    #[variant(VarInt)]
+    pub villager_type: i32,
+    #[variant(VarInt)]
+    pub profession: i32,
+    #[variant(VarInt)]
+    pub level: i32,
+}
+
+/// Source of the vibration particle.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum VibrationSource {
+    Block(BlockPosition),
+    Entity {
+        #[variant(VarInt)]
+        entity_id: i32,
+        eye_height: f32,
+    },
+}
+
+/// Particle with its data. Particle type is an id in the `minecraft:particle_type` registry of 1.20.5,
+/// types outside of it are rejected since their data can not be skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle<'a> {
+    pub particle_type: i32,
+    pub data: ParticleData<'a>,
+}
+
+/// Data of the [Particle], which is read depending on its type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParticleData<'a> {
+    None,
+    /// Block state of `block`, `block_marker`, `falling_dust` and `dust_pillar` particles.
+    BlockState(i32),
+    Dust {
+        color: (f32, f32, f32),
+        scale: f32,
+    },
+    DustColorTransition {
+        from: (f32, f32, f32),
+        to: (f32, f32, f32),
+        scale: f32,
+    },
+    /// ARGB color of `entity_effect` particle.
+    EntityEffect(i32),
+    Item(Slot<'a>),
+    SculkCharge {
+        roll: f32,
+    },
+    Vibration {
+        source: VibrationSource,
+        /// Ticks which particle takes to reach the destination.
+        ticks: i32,
+    },
+    Shriek {
+        delay: i32,
+    },
+}
+
+impl<'a> EntityMetadata<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, index: u8) -> Option<&EntityMetadataValue<'a>> {
+        self.entries.iter()
+            .find(|entry| entry.index == index)
+            .map(|entry| &entry.value)
+    }
+
+    /// Replaces value of the entry with the same index or adds a new entry.
+    pub fn set(&mut self, index: u8, value: EntityMetadataValue<'a>) {
+        match self.entries.iter_mut().find(|entry| entry.index == index) {
+            Some(entry) => entry.value = value,
+            None => self.entries.push(EntityMetadataEntry { index, value }),
+        }
+    }
+}
+
+impl EntityMetadataValue<'_> {
+    /// Returns id of the serializer which is written before the value.
+    pub fn serializer_id(&self) -> i32 {
+        match self {
+            Self::Byte(_) => 0,
+            Self::VarInt(_) => 1,
+            Self::VarLong(_) => 2,
+            Self::Float(_) => 3,
+            Self::String(_) => 4,
+            Self::Component(_) => 5,
+            Self::OptionalComponent(_) => 6,
+            Self::Slot(_) => 7,
+            Self::Boolean(_) => 8,
+            Self::Rotations(..) => 9,
+            Self::Position(_) => 10,
+            Self::OptionalPosition(_) => 11,
+            Self::Direction(_) => 12,
+            Self::OptionalUuid(_) => 13,
+            Self::BlockState(_) => 14,
+            Self::OptionalBlockState(_) => 15,
+            Self::Nbt(_) => 16,
+            Self::Particle(_) => 17,
+            Self::Particles(_) => 18,
+            Self::VillagerData(_) => 19,
+            Self::OptionalVarInt(_) => 20,
+            Self::Pose(_) => 21,
+            Self::CatVariant(_) => 22,
+            Self::WolfVariant(_) => 23,
+            Self::FrogVariant(_) => 24,
+            Self::OptionalGlobalPosition(_) => 25,
+            Self::PaintingVariant(_) => 26,
+            Self::SnifferState(_) => 27,
+            Self::ArmadilloState(_) => 28,
+            Self::Vector3(..) => 29,
+            Self::Quaternion(..) => 30,
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for EntityMetadata<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let mut entries = Vec::new();
+        loop {
+            let index = u8::read(read)?;
+            if index == ENTITY_METADATA_END {
+                break;
+            }
+            entries.push(EntityMetadataEntry { index, value: EntityMetadataValue::read(read)? });
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl PacketWritable for EntityMetadata<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        for entry in &self.entries {
+            if entry.index == ENTITY_METADATA_END {
+                return Err(Error::msg("Entity metadata index 0xFF is reserved for the end"));
+            }
+            entry.index.write(write)?;
+            entry.value.write(write)?;
+        }
+        ENTITY_METADATA_END.write(write)
+    }
+}
+
+impl<'a> PacketReadable<'a> for EntityMetadataValue<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let serializer_id = VarInt::read_variant(read)?;
+        Ok(match serializer_id {
+            0 => Self::Byte(i8::read(read)?),
+            1 => Self::VarInt(VarInt::read_variant(read)?),
+            2 => Self::VarLong(VarLong::read_variant(read)?),
+            3 => Self::Float(f32::read(read)?),
+            4 => Self::String(<&'a str>::read(read)?),
+            5 => Self::Component(ProtocolNbtComponent::read_variant(read)?),
+            6 => Self::OptionalComponent(OptionComponent::read_variant(read)?),
+            7 => Self::Slot(Slot::read(read)?),
+            8 => Self::Boolean(bool::read(read)?),
+            9 => Self::Rotations(f32::read(read)?, f32::read(read)?, f32::read(read)?),
+            10 => Self::Position(BlockPosition::read(read)?),
+            11 => Self::OptionalPosition(Option::read(read)?),
+            12 => Self::Direction(Direction::read(read)?),
+            13 => Self::OptionalUuid(Option::read(read)?),
+            14 => Self::BlockState(VarInt::read_variant(read)?),
+            15 => Self::OptionalBlockState(match VarInt::read_variant(read)? {
+                0 => None,
+                block_state => Some(block_state),
+            }),
+            16 => Self::Nbt(ProtocolNetworkNbt::read_variant(read)?),
+            17 => Self::Particle(Particle::read(read)?),
+            18 => {
+                let length: i32 = VarInt::read_variant(read)?;
+                let mut particles = Vec::with_capacity(length.clamp(0, 64) as usize);
+                for _ in 0..length {
+                    particles.push(Particle::read(read)?);
+                }
+                Self::Particles(particles)
+            }
+            19 => Self::VillagerData(VillagerData::read(read)?),
+            20 => Self::OptionalVarInt(match VarInt::read_variant(read)? {
+                0 => None,
+                value => Some(value - 1),
+            }),
+            21 => Self::Pose(Pose::read(read)?),
+            22 => Self::CatVariant(VarInt::read_variant(read)?),
+            23 => Self::WolfVariant(VarInt::read_variant(read)?),
+            24 => Self::FrogVariant(VarInt::read_variant(read)?),
+            25 => Self::OptionalGlobalPosition(Option::read(read)?),
+            26 => Self::PaintingVariant(VarInt::read_variant(read)?),
+            27 => Self::SnifferState(SnifferState::read(read)?),
+            28 => Self::ArmadilloState(ArmadilloState::read(read)?),
+            29 => Self::Vector3(f32::read(read)?, f32::read(read)?, f32::read(read)?),
+            30 => Self::Quaternion(f32::read(read)?, f32::read(read)?, f32::read(read)?, f32::read(read)?),
+            _ => return Err(PacketReadableError::Any(
+                anyhow::anyhow!("Unknown entity metadata serializer id {}", serializer_id)
+            )),
+        })
+    }
+}
+
+impl PacketWritable for EntityMetadataValue<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.serializer_id(), write)?;
+        match self {
+            Self::Byte(value) => value.write(write),
+            Self::VarInt(value) | Self::BlockState(value) | Self::CatVariant(value) | Self::WolfVariant(value) |
+            Self::FrogVariant(value) | Self::PaintingVariant(value) => VarInt::write_variant(value, write),
+            Self::VarLong(value) => VarLong::write_variant(value, write),
+            Self::Float(value) => value.write(write),
+            Self::String(value) => value.write(write),
+            Self::Component(value) => ProtocolNbtComponent::write_variant(value, write),
+            Self::OptionalComponent(value) => OptionComponent::write_variant(value, write),
+            Self::Slot(value) => value.write(write),
+            Self::Boolean(value) => value.write(write),
+            Self::Rotations(x, y, z) | Self::Vector3(x, y, z) => (*x, *y, *z).write(write),
+            Self::Position(value) => value.write(write),
+            Self::OptionalPosition(value) => value.write(write),
+            Self::Direction(value) => value.write(write),
+            Self::OptionalUuid(value) => value.write(write),
+            Self::OptionalBlockState(value) => VarInt::write_variant(&value.unwrap_or(0), write),
+            Self::Nbt(value) => ProtocolNetworkNbt::write_variant(value, write),
+            Self::Particle(value) => value.write(write),
+            Self::Particles(particles) => {
+                VarInt::write_variant(&(particles.len() as i32), write)?;
+                particles.iter().try_for_each(|particle| particle.write(write))
+            }
+            Self::VillagerData(value) => value.write(write),
+            Self::OptionalVarInt(value) => VarInt::write_variant(&value.map(|value| value + 1).unwrap_or(0), write),
+            Self::Pose(value) => value.write(write),
+            Self::OptionalGlobalPosition(value) => value.write(write),
+            Self::SnifferState(value) => value.write(write),
+            Self::ArmadilloState(value) => value.write(write),
+            Self::Quaternion(x, y, z, w) => {
+                (*x, *y, *z).write(write)?;
+                w.write(write)
+            }
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for Particle<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let particle_type = VarInt::read_variant(read)?;
+        let data = match particle_type {
+            1 | 2 | 28 | 105 => ParticleData::BlockState(VarInt::read_variant(read)?),
+            13 => ParticleData::Dust { color: <(f32, f32, f32)>::read(read)?, scale: f32::read(read)? },
+            14 => ParticleData::DustColorTransition {
+                from: <(f32, f32, f32)>::read(read)?,
+                to: <(f32, f32, f32)>::read(read)?,
+                scale: f32::read(read)?,
+            },
+            20 => ParticleData::EntityEffect(i32::read(read)?),
+            35 => ParticleData::SculkCharge { roll: f32::read(read)? },
+            44 => ParticleData::Item(Slot::read(read)?),
+            45 => ParticleData::Vibration {
+                source: VibrationSource::read(read)?,
+                ticks: VarInt::read_variant(read)?,
+            },
+            99 => ParticleData::Shriek { delay: VarInt::read_variant(read)? },
+            0 | 3..=12 | 15..=19 | 21..=27 | 29..=34 | 36..=43 | 46..=98 | 100..=104 | 106..=108 => ParticleData::None,
+            _ => return Err(PacketReadableError::Any(anyhow::anyhow!("Unknown particle type {}", particle_type))),
+        };
+        Ok(Self { particle_type, data })
+    }
+}
+
+impl PacketWritable for Particle<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.particle_type, write)?;
+        match &self.data {
+            ParticleData::None => Ok(()),
+            ParticleData::BlockState(block_state) => VarInt::write_variant(block_state, write),
+            ParticleData::Dust { color, scale } => {
+                color.write(write)?;
+                scale.write(write)
+            }
+            ParticleData::DustColorTransition { from, to, scale } => {
+                from.write(write)?;
+                to.write(write)?;
+                scale.write(write)
+            }
+            ParticleData::EntityEffect(color) => color.write(write),
+            ParticleData::Item(slot) => slot.write(write),
+            ParticleData::SculkCharge { roll } => roll.write(write),
+            ParticleData::Vibration { source, ticks } => {
+                source.write(write)?;
+                VarInt::write_variant(ticks, write)
+            }
+            ParticleData::Shriek { delay } => VarInt::write_variant(delay, write),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Error;
+use crate::packet::{Packet, PacketReadableError, PacketWritable, PacketWrite};
+use crate::packet_frame::{FrameRead, PacketFrameBuf, packet_data, read_frame, write_frame};
+#[cfg(feature = "compression")]
+use crate::packet_compression::PacketCompression;
+#[cfg(feature = "encryption")]
+use crate::packet_encryption::PacketCipher;
+
+/// Complete packet pipeline of the connection: encryption, framing and compression.
+///
+/// Incoming bytes are given to [PacketPipeline::receive] and then complete packets are taken
+/// by [PacketPipeline::next_frame]. Outgoing packets are written by [PacketPipeline::write_packet].
+#[derive(Default)]
+pub struct PacketPipeline {
+    buffer: Vec<u8>,
+    offset: usize,
+    #[cfg(feature = "compression")]
+    compression: Option<PacketCompression>,
+    #[cfg(feature = "encryption")]
+    cipher: Option<PacketCipher>,
+}
+
+impl PacketPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "compression")]
+    pub fn compression(&self) -> Option<&PacketCompression> {
+        self.compression.as_ref()
+    }
+
+    /// Sets compression for frames that are not taken yet and for all next written packets.
+    #[cfg(feature = "compression")]
+    pub fn set_compression(&mut self, compression: Option<PacketCompression>) {
+        self.compression = compression;
+    }
+
+    #[cfg(feature = "encryption")]
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Enables encryption for all bytes that are not taken as frames yet and for all next written packets.
+    ///
+    /// Bytes that are already received, but not taken as frames are decrypted,
+    /// because they were sent after the packet that enabled encryption.
+    #[cfg(feature = "encryption")]
+    pub fn enable_encryption(&mut self, mut cipher: PacketCipher) {
+        cipher.decrypt(&mut self.buffer[self.offset..]);
+        self.cipher = Some(cipher);
+    }
+
+    /// Count of received bytes that are not taken as frames yet.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.offset
+    }
+
+    pub fn receive(&mut self, bytes: &[u8]) {
+        if self.offset != 0 {
+            self.buffer.drain(..self.offset);
+            self.offset = 0;
+        }
+        #[cfg(feature = "encryption")]
+        if let Some(ref mut cipher) = self.cipher {
+            let start = self.buffer.len();
+            self.buffer.extend_from_slice(bytes);
+            cipher.decrypt(&mut self.buffer[start..]);
+            return;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Takes next complete frame. Returns [None] if more bytes are needed.
+    pub fn next_frame(&mut self) -> Result<Option<PacketFrameBuf>, PacketReadableError> {
+        let (frame, consumed) = match read_frame(&self.buffer[self.offset..])? {
+            FrameRead::Frame { frame, consumed } => (frame, consumed),
+            FrameRead::NeedMore => return Ok(None),
+        };
+        self.offset += consumed;
+        #[cfg(feature = "compression")]
+        let result = match self.compression {
+            Some(ref compression) => PacketFrameBuf::new(&compression.decompress(frame)?),
+            None => PacketFrameBuf::new(frame),
+        };
+        #[cfg(not(feature = "compression"))]
+        let result = PacketFrameBuf::new(frame);
+        result.map(Some)
+    }
+
+    /// Writes packet data (id and packet itself) as frame.
+    pub fn write_data<W>(&mut self, data: &[u8], write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        let mut frame = Vec::with_capacity(data.len() + 6);
+        #[cfg(feature = "compression")]
+        match self.compression {
+            Some(ref compression) => compression.write_frame(data, &mut frame)?,
+            None => write_frame(data, &mut frame)?,
+        }
+        #[cfg(not(feature = "compression"))]
+        write_frame(data, &mut frame)?;
+        #[cfg(feature = "encryption")]
+        if let Some(ref mut cipher) = self.cipher {
+            cipher.encrypt(frame.as_mut_slice());
+        }
+        write.write_bytes_owned(frame)
+    }
+
+    pub fn write_packet<T, W>(&mut self, packet: &T, write: &mut W) -> Result<(), Error>
+        where T: Packet + PacketWritable, W: PacketWrite {
+        self.write_data(&packet_data(packet)?, write)
+    }
+}
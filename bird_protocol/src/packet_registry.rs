@@ -0,0 +1,119 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use anyhow::Error;
+use crate::packet::{Packet, PacketBound, PacketReadable, PacketReadableError, PacketState, PacketVariantWritable, PacketWritable, PacketWrite, SlicePacketRead, PacketRead};
+use crate::packet_frame::PacketFrame;
+use crate::packet_types::VarInt;
+
+/// Packet decoded by [PacketRegistry]. Use [Box::downcast] to get the packet itself.
+pub type AnyPacket = Box<dyn Any + Send>;
+
+type DecodeFn = fn(&[u8]) -> Result<AnyPacket, PacketReadableError>;
+
+type EncodeFn = fn(&dyn Any, &mut Vec<u8>) -> Result<(), Error>;
+
+/// Key of the packet in [PacketRegistry].
+pub type PacketKey = (PacketState, PacketBound, i32);
+
+struct RegistryEntry {
+    type_id: TypeId,
+    decode: DecodeFn,
+    encode: EncodeFn,
+}
+
+/// Registry of packets which are known only at runtime, for example packets of mods or plugins.
+#[derive(Default)]
+pub struct PacketRegistry {
+    entries: HashMap<PacketKey, RegistryEntry>,
+    keys: HashMap<TypeId, PacketKey>,
+}
+
+fn decode_packet<T>(body: &[u8]) -> Result<AnyPacket, PacketReadableError>
+    where T: for<'a> PacketReadable<'a> + Send + 'static {
+    let mut read = SlicePacketRead::new(body);
+    let packet = T::read(&mut read)?;
+    match read.available() {
+        0 => Ok(Box::new(packet)),
+        _ => Err(PacketReadableError::Any(Error::msg("Packet is not fully read"))),
+    }
+}
+
+fn encode_packet<T>(packet: &dyn Any, write: &mut Vec<u8>) -> Result<(), Error>
+    where T: PacketWritable + 'static {
+    match packet.downcast_ref::<T>() {
+        Some(packet) => packet.write(write),
+        None => Err(Error::msg("Bad packet type")),
+    }
+}
+
+impl PacketRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers packet with its own state, bound and id.
+    pub fn register<T>(&mut self) -> Result<(), Error>
+        where T: Packet + for<'a> PacketReadable<'a> + PacketWritable + Send + 'static {
+        self.register_with_key::<T>((T::state(), T::bound(), T::id()))
+    }
+
+    /// Registers packet with the given state, bound and id. Useful if id is assigned at runtime.
+    pub fn register_with_key<T>(&mut self, key: PacketKey) -> Result<(), Error>
+        where T: for<'a> PacketReadable<'a> + PacketWritable + Send + 'static {
+        let type_id = TypeId::of::<T>();
+        if self.entries.contains_key(&key) {
+            return Err(Error::msg("Packet with this key is already registered"));
+        }
+        if self.keys.contains_key(&type_id) {
+            return Err(Error::msg("Packet type is already registered"));
+        }
+        self.entries.insert(key, RegistryEntry {
+            type_id,
+            decode: decode_packet::<T>,
+            encode: encode_packet::<T>,
+        });
+        self.keys.insert(type_id, key);
+        Ok(())
+    }
+
+    /// Removes packet from the registry. Returns true if it was registered.
+    pub fn unregister(&mut self, key: PacketKey) -> bool {
+        match self.entries.remove(&key) {
+            Some(entry) => {
+                self.keys.remove(&entry.type_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, key: PacketKey) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    /// Returns key of the registered packet type.
+    pub fn key_of<T: 'static>(&self) -> Option<PacketKey> {
+        self.keys.get(&TypeId::of::<T>()).copied()
+    }
+
+    /// Decodes frame. Returns [None] if packet with such id is not registered for the given state and bound.
+    pub fn decode(
+        &self, state: PacketState, bound: PacketBound, frame: PacketFrame<'_>,
+    ) -> Result<Option<AnyPacket>, PacketReadableError> {
+        match self.entries.get(&(state, bound, frame.id)) {
+            Some(entry) => (entry.decode)(frame.body).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Writes id of the registered packet and then packet itself.
+    pub fn encode<W>(&self, packet: &dyn Any, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        let key = self.keys.get(&packet.type_id())
+            .ok_or_else(|| Error::msg("Packet type is not registered"))?;
+        let entry = &self.entries[key];
+        let mut data = Vec::new();
+        VarInt::write_variant(&key.2, &mut data)?;
+        (entry.encode)(packet, &mut data)?;
+        write.write_bytes_owned(data)
+    }
+}
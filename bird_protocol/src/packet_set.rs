@@ -0,0 +1,53 @@
+use anyhow::Error;
+use crate::packet::{PacketBound, PacketReadableError, PacketState, PacketWrite};
+use crate::packet_frame::PacketFrame;
+
+/// Set of packets of one state and bound, which are dispatched by id.
+/// Implemented by `PacketSet` derive for enums which variants wrap packets.
+pub trait PacketSet<'a>: Sized {
+    fn state() -> PacketState;
+
+    fn bound() -> PacketBound;
+
+    /// Id of the wrapped packet.
+    fn id(&self) -> i32;
+
+    /// Reads packet which id is the id of the frame.
+    fn read_packet(frame: PacketFrame<'a>) -> Result<Self, PacketSetError<'a>>;
+
+    /// Writes id of the wrapped packet and then packet itself.
+    fn write_packet<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite;
+
+    /// Id of the wrapped packet in the protocol version, [None] if packet does not exist in it.
+    fn id_for(&self, version: i32) -> Option<i32>;
+
+    /// Reads packet which id in the protocol version is the id of the frame, with the layout of this version.
+    fn read_packet_versioned(frame: PacketFrame<'a>, version: i32) -> Result<Self, PacketSetError<'a>>;
+
+    /// Writes id and layout of the wrapped packet in the protocol version.
+    /// Fails if packet does not exist in this version.
+    fn write_packet_versioned<W>(&self, write: &mut W, version: i32) -> Result<(), Error> where W: PacketWrite;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PacketSetError<'a> {
+    #[error("Unknown packet id {} for {:?} {:?}", .0.id, .0.state, .0.bound)]
+    UnknownId(UnknownPacketId<'a>),
+    #[error("{0}")]
+    Read(#[from] PacketReadableError),
+}
+
+/// Frame which id is not known by [PacketSet].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnknownPacketId<'a> {
+    pub state: PacketState,
+    pub bound: PacketBound,
+    pub id: i32,
+    pub body: &'a [u8],
+}
+
+impl<'a> UnknownPacketId<'a> {
+    pub fn frame(&self) -> PacketFrame<'a> {
+        PacketFrame { id: self.id, body: self.body }
+    }
+}
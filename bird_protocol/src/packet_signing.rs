@@ -0,0 +1,124 @@
+use anyhow::Error;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use rsa::pkcs8::DecodePublicKey;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::packet_chat::MessageSignature;
+use crate::packet_default::{LoginStartSignatureData, PlayPlayerSession};
+
+/// Version of the signed message format, which is the first field of the signed data since 1.19.3.
+const MESSAGE_SIGNATURE_VERSION: i32 = 1;
+
+/// Mojang services keys which are signing player public keys.
+/// These are `playerCertificateKeys` from `https://api.minecraftservices.com/publickeys` decoded from base64.
+#[derive(Debug, Clone, Default)]
+pub struct ServicesKeys {
+    keys: Vec<RsaPublicKey>,
+}
+
+/// Player public key which is verified by [ServicesKeys]. Chat messages are signed by its private key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerPublicKey {
+    pub key: RsaPublicKey,
+    /// Milliseconds since the unix epoch.
+    pub expires_at: i64,
+}
+
+/// Body of the chat message which is signed by the player since 1.19.3.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedMessageBody<'a> {
+    pub sender: Uuid,
+    pub session_id: Uuid,
+    /// Index of the message in the sender chain, starts from 0 for each session.
+    pub index: i32,
+    pub salt: i64,
+    /// Milliseconds since the unix epoch, only seconds are signed.
+    pub timestamp: i64,
+    pub message: &'a str,
+    /// Signatures of the last seen messages from the oldest to the newest.
+    pub last_seen: &'a [MessageSignature],
+}
+
+impl ServicesKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds X.509 der encoded key.
+    pub fn add_der(&mut self, der: &[u8]) -> Result<(), Error> {
+        self.keys.push(RsaPublicKey::from_public_key_der(der).map_err(|err| anyhow::anyhow!("{}", err))?);
+        Ok(())
+    }
+
+    pub fn with_der(mut self, der: &[u8]) -> Result<Self, Error> {
+        self.add_der(der)?;
+        Ok(self)
+    }
+
+    /// Verifies public key of the player, signature is SHA1 with RSA over the player uuid,
+    /// expiration time and der encoded key.
+    pub fn verify_player_key(
+        &self, player: Uuid, expires_at: i64, public_key: &[u8], key_signature: &[u8],
+    ) -> Result<PlayerPublicKey, Error> {
+        let mut hasher = Sha1::new();
+        hasher.update(player.as_bytes());
+        hasher.update(expires_at.to_be_bytes());
+        hasher.update(public_key);
+        let hash = hasher.finalize();
+        match self.keys.iter().any(|key| key.verify(Pkcs1v15Sign::new::<Sha1>(), &hash, key_signature).is_ok()) {
+            true => PlayerPublicKey::from_der(public_key, expires_at),
+            false => Err(Error::msg("Player public key is not signed by Mojang")),
+        }
+    }
+
+    /// Verifies key which is sent in the login start packet since 1.19.1.
+    pub fn verify_login(&self, player: Uuid, data: &LoginStartSignatureData) -> Result<PlayerPublicKey, Error> {
+        self.verify_player_key(player, data.timestamp, data.public_key, data.signature)
+    }
+
+    pub fn verify_session(&self, player: Uuid, session: &PlayPlayerSession) -> Result<PlayerPublicKey, Error> {
+        self.verify_player_key(player, session.expires_at, session.public_key, session.key_signature)
+    }
+}
+
+impl PlayerPublicKey {
+    /// Reads X.509 der encoded key.
+    pub fn from_der(der: &[u8], expires_at: i64) -> Result<Self, Error> {
+        Ok(Self {
+            key: RsaPublicKey::from_public_key_der(der).map_err(|err| anyhow::anyhow!("{}", err))?,
+            expires_at,
+        })
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at < now
+    }
+
+    /// Verifies SHA256 with RSA signature of the message.
+    pub fn verify_message(&self, body: &SignedMessageBody, signature: &[u8]) -> Result<(), Error> {
+        let hash = Sha256::digest(body.signed_data());
+        self.key.verify(Pkcs1v15Sign::new::<Sha256>(), &hash, signature)
+            .map_err(|_| Error::msg("Bad message signature"))
+    }
+}
+
+impl SignedMessageBody<'_> {
+    /// Returns data which is signed by the player.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(64 + self.message.len() + self.last_seen.len() * 256);
+        data.extend_from_slice(&MESSAGE_SIGNATURE_VERSION.to_be_bytes());
+        data.extend_from_slice(self.sender.as_bytes());
+        data.extend_from_slice(self.session_id.as_bytes());
+        data.extend_from_slice(&self.index.to_be_bytes());
+        data.extend_from_slice(&self.salt.to_be_bytes());
+        data.extend_from_slice(&self.timestamp.div_euclid(1000).to_be_bytes());
+        data.extend_from_slice(&(self.message.len() as i32).to_be_bytes());
+        data.extend_from_slice(self.message.as_bytes());
+        data.extend_from_slice(&(self.last_seen.len() as i32).to_be_bytes());
+        for signature in self.last_seen {
+            data.extend_from_slice(signature);
+        }
+        data
+    }
+}
@@ -0,0 +1,1069 @@
+use std::borrow::Cow;
+use anyhow::Error;
+use bird_chat::component::Component;
+use bird_chat::identifier::Identifier;
+use uuid::Uuid;
+use crate::*;
+use crate::derive::*;
+
+/// Item slot, which is [None] if it is empty.
+///
+/// Before 1.20.5 item is written with its nbt, since 1.20.5 it is written with added and removed components.
+/// Components are read with their 1.20.5 layouts and their lengths are not sent, so reading stops with an error
+/// at the first component which is not modelled by [ItemComponent], and the rest of the packet can not be read.
+/// Slots which are nested by the components deeper than [MAX_NESTING_DEPTH] are rejected.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Slot<'a>(pub Option<ItemStack<'a>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack<'a> {
+    /// Id in the `minecraft:item` registry.
+    pub item_id: i32,
+    pub count: i32,
+    /// Nbt of the item, which is sent only before 1.20.5.
+    pub nbt: Option<fastnbt::Value>,
+    /// Components which are added to the item prototype, sent since 1.20.5.
+    pub components: Vec<ItemComponent<'a>>,
+    /// Ids of the components which are removed from the item prototype, sent since 1.20.5.
+    pub removed_components: Vec<i32>,
+}
+
+/// Item data component. Ids are the ids of the `minecraft:data_component_type` registry of 1.20.5.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemComponent<'a> {
+    CustomData(fastnbt::Value),
+    MaxStackSize(i32),
+    MaxDamage(i32),
+    Damage(i32),
+    Unbreakable {
+        show_in_tooltip: bool,
+    },
+    CustomName(Component<'a>),
+    ItemName(Component<'a>),
+    Lore(Vec<Component<'a>>),
+    Rarity(Rarity),
+    Enchantments {
+        /// Enchantment id and its level.
+        enchantments: Vec<(i32, i32)>,
+        show_in_tooltip: bool,
+    },
+    CanPlaceOn {
+        predicates: Vec<BlockPredicate<'a>>,
+        show_in_tooltip: bool,
+    },
+    CanBreak {
+        predicates: Vec<BlockPredicate<'a>>,
+        show_in_tooltip: bool,
+    },
+    AttributeModifiers {
+        modifiers: Vec<AttributeModifier<'a>>,
+        show_in_tooltip: bool,
+    },
+    CustomModelData(i32),
+    HideAdditionalTooltip,
+    HideTooltip,
+    RepairCost(i32),
+    CreativeSlotLock,
+    EnchantmentGlintOverride(bool),
+    /// Written as an empty compound.
+    IntangibleProjectile,
+    Food(Food),
+    FireResistant,
+    Tool(Tool<'a>),
+    StoredEnchantments {
+        enchantments: Vec<(i32, i32)>,
+        show_in_tooltip: bool,
+    },
+    DyedColor {
+        color: i32,
+        show_in_tooltip: bool,
+    },
+    MapColor(i32),
+    MapId(i32),
+    MapDecorations(fastnbt::Value),
+    MapPostProcessing(i32),
+    ChargedProjectiles(Vec<Slot<'a>>),
+    BundleContents(Vec<Slot<'a>>),
+    PotionContents(PotionContents),
+    SuspiciousStewEffects(Vec<SuspiciousStewEffect>),
+    WritableBookContent {
+        pages: Vec<FilterableText<'a>>,
+    },
+    WrittenBookContent(WrittenBookContent<'a>),
+    Trim(Box<ArmorTrim<'a>>),
+    DebugStickState(fastnbt::Value),
+    EntityData(fastnbt::Value),
+    BucketEntityData(fastnbt::Value),
+    BlockEntityData(fastnbt::Value),
+    Instrument(Holder<Instrument<'a>>),
+    OminousBottleAmplifier(i32),
+    /// Ids of the recipes which are unlocked by the knowledge book, written as nbt list of strings.
+    Recipes(Vec<Cow<'a, str>>),
+    LodestoneTracker {
+        target: Option<GlobalPosition<'a>>,
+        tracked: bool,
+    },
+    FireworkExplosion(FireworkExplosion),
+    Fireworks {
+        flight_duration: i32,
+        explosions: Vec<FireworkExplosion>,
+    },
+    Profile(GameProfile<'a>),
+    NoteBlockSound(Identifier<'a>),
+    BannerPatterns(Vec<BannerPatternLayer<'a>>),
+    /// Id of the dye color.
+    BaseColor(i32),
+    /// Item ids of the decorated pot sides.
+    PotDecorations(Vec<i32>),
+    Container(Vec<Slot<'a>>),
+    /// Block state property names and values.
+    BlockState(Vec<(Cow<'a, str>, Cow<'a, str>)>),
+    Bees(Vec<Bee>),
+    /// Name of the item which opens the container, written as nbt string.
+    Lock(Cow<'a, str>),
+    ContainerLoot(fastnbt::Value),
+    /// Component which is not modelled, data is written as is.
+    /// Such components can not be read, because their length is not known.
+    Raw {
+        id: i32,
+        data: Cow<'a, [u8]>,
+    },
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum Rarity {
+    Common = 0,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+/// Registry entry which is sent as its id, or as the value itself if it is not registered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Holder<T> {
+    Registry(i32),
+    Inline(T),
+}
+
+/// Registry entries which are sent as a tag name or as a list of ids.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdSet<'a> {
+    Tag(Identifier<'a>),
+    Ids(Vec<i32>),
+}
+
+type OptionVarInt = OptionVariant<VarInt, i32>;
+type OptionNetworkNbt = OptionVariant<ProtocolNetworkNbt, fastnbt::Value>;
+type OptionNbtComponent<'a> = OptionVariant<ProtocolNbtComponent, Component<'a>>;
+type IntArray = LengthProvidedSlice<VarInt, i32, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct BlockPredicate<'a> {
+    pub blocks: Option<IdSet<'a>>,
+    #[variant(OptionPropertyMatcherArray)]
+    pub properties: Option<Vec<PropertyMatcher<'a>>>,
+    #[variant(OptionNetworkNbt)]
+    pub nbt: Option<fastnbt::Value>,
+}
+
+type OptionPropertyMatcherArray<'a> = OptionVariant<
+    LengthProvidedSlice<VarInt, PropertyMatcher<'a>, i32>,
+    Vec<PropertyMatcher<'a>>,
+>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct PropertyMatcher<'a> {
+    pub name: Cow<'a, str>,
+    pub value: PropertyValue<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue<'a> {
+    Exact(Cow<'a, str>),
+    Range {
+        min: Option<Cow<'a, str>>,
+        max: Option<Cow<'a, str>>,
+    },
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct AttributeModifier<'a> {
+    /// Id in the `minecraft:attribute` registry.
+    #[variant(VarInt)]
+    pub attribute_id: i32,
+    pub uuid: Uuid,
+    pub name: Cow<'a, str>,
+    pub amount: f64,
+    pub operation: AttributeOperation,
+    /// Id of the equipment slot group, zero is any slot.
+    #[variant(VarInt)]
+    pub slot: i32,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum AttributeOperation {
+    AddValue = 0,
+    AddMultipliedBase,
+    AddMultipliedTotal,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct PotionEffect {
+    /// Id in the `minecraft:mob_effect` registry.
+    #[variant(VarInt)]
+    pub effect_id: i32,
+    pub details: PotionEffectDetails,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PotionEffectDetails {
+    pub amplifier: i32,
+    /// Duration in ticks, -1 is infinite.
+    pub duration: i32,
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+    /// Effect of the same type which is applied when this one ends.
+    pub hidden_effect: Option<Box<PotionEffectDetails>>,
+}
+
+type PotionEffectArray = LengthProvidedSlice<VarInt, PotionEffect, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct Food {
+    #[variant(VarInt)]
+    pub nutrition: i32,
+    pub saturation: f32,
+    pub can_always_eat: bool,
+    pub eat_seconds: f32,
+    #[variant(FoodEffectArray)]
+    pub effects: Vec<FoodEffect>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct FoodEffect {
+    pub effect: PotionEffect,
+    pub probability: f32,
+}
+
+type FoodEffectArray = LengthProvidedSlice<VarInt, FoodEffect, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct Tool<'a> {
+    #[variant(ToolRuleArray)]
+    pub rules: Vec<ToolRule<'a>>,
+    pub default_mining_speed: f32,
+    #[variant(VarInt)]
+    pub damage_per_block: i32,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ToolRule<'a> {
+    pub blocks: IdSet<'a>,
+    pub speed: Option<f32>,
+    pub correct_for_drops: Option<bool>,
+}
+
+type ToolRuleArray<'a> = LengthProvidedSlice<VarInt, ToolRule<'a>, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq, Default)]
+pub struct PotionContents {
+    /// Id in the `minecraft:potion` registry.
+    #[variant(OptionVarInt)]
+    pub potion_id: Option<i32>,
+    pub custom_color: Option<i32>,
+    #[variant(PotionEffectArray)]
+    pub custom_effects: Vec<PotionEffect>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuspiciousStewEffect {
+    #[variant(VarInt)]
+    pub effect_id: i32,
+    #[variant(VarInt)]
+    pub duration: i32,
+}
+
+/// Text with its version which is filtered by the server chat filter.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct FilterableText<'a> {
+    pub raw: Cow<'a, str>,
+    pub filtered: Option<Cow<'a, str>>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct FilterableComponent<'a> {
+    #[variant(ProtocolNbtComponent)]
+    pub raw: Component<'a>,
+    #[variant(OptionNbtComponent)]
+    pub filtered: Option<Component<'a>>,
+}
+
+type FilterableComponentArray<'a> = LengthProvidedSlice<VarInt, FilterableComponent<'a>, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct WrittenBookContent<'a> {
+    pub title: FilterableText<'a>,
+    pub author: Cow<'a, str>,
+    /// Zero is original, one is copy of original, two is copy of copy.
+    #[variant(VarInt)]
+    pub generation: i32,
+    #[variant(FilterableComponentArray)]
+    pub pages: Vec<FilterableComponent<'a>>,
+    pub resolved: bool,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ArmorTrim<'a> {
+    pub material: Holder<TrimMaterial<'a>>,
+    pub pattern: Holder<TrimPattern<'a>>,
+    pub show_in_tooltip: bool,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct TrimMaterial<'a> {
+    pub asset_name: Cow<'a, str>,
+    /// Id in the `minecraft:item` registry.
+    #[variant(VarInt)]
+    pub ingredient: i32,
+    pub item_model_index: f32,
+    #[variant(ArmorMaterialOverrideArray)]
+    pub override_armor_materials: Vec<ArmorMaterialOverride<'a>>,
+    #[variant(ProtocolNbtComponent)]
+    pub description: Component<'a>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ArmorMaterialOverride<'a> {
+    /// Id in the `minecraft:armor_material` registry.
+    #[variant(VarInt)]
+    pub armor_material: i32,
+    pub asset_name: Cow<'a, str>,
+}
+
+type ArmorMaterialOverrideArray<'a> = LengthProvidedSlice<VarInt, ArmorMaterialOverride<'a>, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct TrimPattern<'a> {
+    pub asset_id: Identifier<'a>,
+    /// Id in the `minecraft:item` registry.
+    #[variant(VarInt)]
+    pub template_item: i32,
+    #[variant(ProtocolNbtComponent)]
+    pub description: Component<'a>,
+    pub decal: bool,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct Instrument<'a> {
+    pub sound_event: Holder<SoundEvent<'a>>,
+    /// Duration in ticks.
+    #[variant(VarInt)]
+    pub use_duration: i32,
+    pub range: f32,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct SoundEvent<'a> {
+    pub sound_name: Identifier<'a>,
+    pub fixed_range: Option<f32>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct GlobalPosition<'a> {
+    pub dimension: Identifier<'a>,
+    pub position: BlockPosition,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct FireworkExplosion {
+    pub shape: FireworkShape,
+    #[variant(IntArray)]
+    pub colors: Vec<i32>,
+    #[variant(IntArray)]
+    pub fade_colors: Vec<i32>,
+    pub has_trail: bool,
+    pub has_twinkle: bool,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, Copy, PartialEq, Eq)]
+#[enum_type(i32)]
+#[enum_variant(VarInt)]
+pub enum FireworkShape {
+    SmallBall = 0,
+    LargeBall,
+    Star,
+    Creeper,
+    Burst,
+}
+
+/// Player profile of the player head.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq, Default)]
+pub struct GameProfile<'a> {
+    pub name: Option<Cow<'a, str>>,
+    pub uuid: Option<Uuid>,
+    #[variant(ProfilePropertyArray)]
+    pub properties: Vec<ProfileProperty<'a>>,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct ProfileProperty<'a> {
+    pub name: Cow<'a, str>,
+    pub value: Cow<'a, str>,
+    pub signature: Option<Cow<'a, str>>,
+}
+
+type ProfilePropertyArray<'a> = LengthProvidedSlice<VarInt, ProfileProperty<'a>, i32>;
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct BannerPatternLayer<'a> {
+    pub pattern: Holder<BannerPattern<'a>>,
+    /// Id of the dye color.
+    #[variant(VarInt)]
+    pub color: i32,
+}
+
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct BannerPattern<'a> {
+    pub asset_id: Identifier<'a>,
+    pub translation_key: Cow<'a, str>,
+}
+
+/// Bee which is inside of the beehive item.
+#[derive(PacketWritable, PacketReadable, Debug, Clone, PartialEq)]
+pub struct Bee {
+    #[variant(ProtocolNetworkNbt)]
+    pub entity_data: fastnbt::Value,
+    #[variant(VarInt)]
+    pub ticks_in_hive: i32,
+    #[variant(VarInt)]
+    pub min_ticks_in_hive: i32,
+}
+
+impl<'a> Slot<'a> {
+    pub fn empty() -> Self {
+        Self(None)
+    }
+
+    pub fn new(item_id: i32, count: i32) -> Self {
+        Self(Some(ItemStack::new(item_id, count)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn item(&self) -> Option<&ItemStack<'a>> {
+        self.0.as_ref()
+    }
+}
+
+impl<'a> ItemStack<'a> {
+    pub fn new(item_id: i32, count: i32) -> Self {
+        Self { item_id, count, nbt: None, components: Vec::new(), removed_components: Vec::new() }
+    }
+
+    pub fn component(&self, id: i32) -> Option<&ItemComponent<'a>> {
+        self.components.iter().find(|component| component.id() == id)
+    }
+
+    /// Replaces component with the same id or adds a new one.
+    pub fn set_component(&mut self, component: ItemComponent<'a>) {
+        self.removed_components.retain(|id| *id != component.id());
+        match self.components.iter_mut().find(|added| added.id() == component.id()) {
+            Some(added) => *added = component,
+            None => self.components.push(component),
+        }
+    }
+
+    pub fn custom_name(&self) -> Option<&Component<'a>> {
+        match self.component(ItemComponent::CUSTOM_NAME) {
+            Some(ItemComponent::CustomName(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn damage(&self) -> Option<i32> {
+        match self.component(ItemComponent::DAMAGE) {
+            Some(ItemComponent::Damage(damage)) => Some(*damage),
+            _ => None,
+        }
+    }
+
+    pub fn custom_data(&self) -> Option<&fastnbt::Value> {
+        match self.component(ItemComponent::CUSTOM_DATA) {
+            Some(ItemComponent::CustomData(data)) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// Items inside of the shulker box or other container item.
+    pub fn container(&self) -> Option<&[Slot<'a>]> {
+        match self.component(ItemComponent::CONTAINER) {
+            Some(ItemComponent::Container(slots)) => Some(slots),
+            _ => None,
+        }
+    }
+}
+
+impl ItemComponent<'_> {
+    pub const CUSTOM_DATA: i32 = 0;
+    pub const DAMAGE: i32 = 3;
+    pub const CUSTOM_NAME: i32 = 5;
+    pub const CONTAINER: i32 = 51;
+
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::CustomData(_) => Self::CUSTOM_DATA,
+            Self::MaxStackSize(_) => 1,
+            Self::MaxDamage(_) => 2,
+            Self::Damage(_) => Self::DAMAGE,
+            Self::Unbreakable { .. } => 4,
+            Self::CustomName(_) => Self::CUSTOM_NAME,
+            Self::ItemName(_) => 6,
+            Self::Lore(_) => 7,
+            Self::Rarity(_) => 8,
+            Self::Enchantments { .. } => 9,
+            Self::CanPlaceOn { .. } => 10,
+            Self::CanBreak { .. } => 11,
+            Self::AttributeModifiers { .. } => 12,
+            Self::CustomModelData(_) => 13,
+            Self::HideAdditionalTooltip => 14,
+            Self::HideTooltip => 15,
+            Self::RepairCost(_) => 16,
+            Self::CreativeSlotLock => 17,
+            Self::EnchantmentGlintOverride(_) => 18,
+            Self::IntangibleProjectile => 19,
+            Self::Food(_) => 20,
+            Self::FireResistant => 21,
+            Self::Tool(_) => 22,
+            Self::StoredEnchantments { .. } => 23,
+            Self::DyedColor { .. } => 24,
+            Self::MapColor(_) => 25,
+            Self::MapId(_) => 26,
+            Self::MapDecorations(_) => 27,
+            Self::MapPostProcessing(_) => 28,
+            Self::ChargedProjectiles(_) => 29,
+            Self::BundleContents(_) => 30,
+            Self::PotionContents(_) => 31,
+            Self::SuspiciousStewEffects(_) => 32,
+            Self::WritableBookContent { .. } => 33,
+            Self::WrittenBookContent(_) => 34,
+            Self::Trim(_) => 35,
+            Self::DebugStickState(_) => 36,
+            Self::EntityData(_) => 37,
+            Self::BucketEntityData(_) => 38,
+            Self::BlockEntityData(_) => 39,
+            Self::Instrument(_) => 40,
+            Self::OminousBottleAmplifier(_) => 41,
+            Self::Recipes(_) => 42,
+            Self::LodestoneTracker { .. } => 43,
+            Self::FireworkExplosion(_) => 44,
+            Self::Fireworks { .. } => 45,
+            Self::Profile(_) => 46,
+            Self::NoteBlockSound(_) => 47,
+            Self::BannerPatterns(_) => 48,
+            Self::BaseColor(_) => 49,
+            Self::PotDecorations(_) => 50,
+            Self::Container(_) => Self::CONTAINER,
+            Self::BlockState(_) => 52,
+            Self::Bees(_) => 53,
+            Self::Lock(_) => 54,
+            Self::ContainerLoot(_) => 55,
+            Self::Raw { id, .. } => *id,
+        }
+    }
+}
+
+fn read_list<'a, R, T>(
+    read: &mut R, mut read_value: impl FnMut(&mut R) -> Result<T, PacketReadableError>,
+) -> Result<Vec<T>, PacketReadableError> where R: PacketRead<'a> {
+    let length: i32 = VarInt::read_variant(read)?;
+    let mut values = Vec::with_capacity(length.clamp(0, 64) as usize);
+    for _ in 0..length {
+        values.push(read_value(read)?);
+    }
+    Ok(values)
+}
+
+fn write_list<W, T>(
+    values: &[T], write: &mut W, mut write_value: impl FnMut(&T, &mut W) -> Result<(), Error>,
+) -> Result<(), Error> where W: PacketWrite {
+    VarInt::write_variant(&(values.len() as i32), write)?;
+    values.iter().try_for_each(|value| write_value(value, write))
+}
+
+fn read_enchantments<'a, R>(read: &mut R) -> Result<(Vec<(i32, i32)>, bool), PacketReadableError> where R: PacketRead<'a> {
+    let enchantments = read_list(read, |read| Ok((VarInt::read_variant(read)?, VarInt::read_variant(read)?)))?;
+    Ok((enchantments, bool::read(read)?))
+}
+
+fn write_enchantments<W>(enchantments: &[(i32, i32)], show_in_tooltip: bool, write: &mut W) -> Result<(), Error>
+    where W: PacketWrite {
+    write_list(enchantments, write, |(id, level), write| {
+        VarInt::write_variant(id, write)?;
+        VarInt::write_variant(level, write)
+    })?;
+    show_in_tooltip.write(write)
+}
+
+fn read_predicates<'a, R>(read: &mut R) -> Result<(Vec<BlockPredicate<'a>>, bool), PacketReadableError>
+    where R: PacketRead<'a> {
+    Ok((read_list(read, BlockPredicate::read)?, bool::read(read)?))
+}
+
+/// Maximum depth of the slots and potion effects which are nested into each other by the item components.
+pub const MAX_NESTING_DEPTH: usize = 32;
+
+const LIST_TAG: u8 = 9;
+const STRING_TAG: u8 = 8;
+
+fn read_nbt_string<'a, R>(read: &mut R) -> Result<Cow<'a, str>, PacketReadableError> where R: PacketRead<'a> {
+    let length = u16::read(read)?;
+    std::str::from_utf8(read.take_slice(length as usize)?)
+        .map(Cow::Borrowed)
+        .map_err(|err| PacketReadableError::Any(err.into()))
+}
+
+fn write_nbt_string<W>(text: &str, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+    u16::try_from(text.len()).map_err(|_| Error::msg("Too big string"))?.write(write)?;
+    write.write_bytes(text.as_bytes())
+}
+
+fn expect_tag<'a, R>(read: &mut R, expected: u8) -> Result<(), PacketReadableError> where R: PacketRead<'a> {
+    match u8::read(read)? {
+        tag if tag == expected => Ok(()),
+        tag => Err(PacketReadableError::Any(anyhow::anyhow!("Expected nbt tag {}, got {}", expected, tag))),
+    }
+}
+
+/// Reads network nbt which is a list of strings, empty list may have the end tag as its element tag.
+fn read_nbt_string_list<'a, R>(read: &mut R) -> Result<Vec<Cow<'a, str>>, PacketReadableError> where R: PacketRead<'a> {
+    expect_tag(read, LIST_TAG)?;
+    let tag = u8::read(read)?;
+    let length = i32::read(read)?;
+    if length > 0 && tag != STRING_TAG {
+        return Err(PacketReadableError::Any(anyhow::anyhow!("Expected list of strings, got list of {}", tag)));
+    }
+    let mut values = Vec::with_capacity(length.clamp(0, 64) as usize);
+    for _ in 0..length {
+        values.push(read_nbt_string(read)?);
+    }
+    Ok(values)
+}
+
+fn write_nbt_string_list<W>(values: &[Cow<str>], write: &mut W) -> Result<(), Error> where W: PacketWrite {
+    write.write_byte(LIST_TAG)?;
+    write.write_byte(STRING_TAG)?;
+    (values.len() as i32).write(write)?;
+    values.iter().try_for_each(|value| write_nbt_string(value, write))
+}
+
+impl<'a, T: PacketReadable<'a>> PacketReadable<'a> for Holder<T> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        // Registry id is written increased by one and zero means that the value is following
+        Ok(match VarInt::read_variant(read)? {
+            0 => Self::Inline(T::read(read)?),
+            id => Self::Registry(id - 1),
+        })
+    }
+}
+
+impl<T: PacketWritable> PacketWritable for Holder<T> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        match self {
+            Self::Registry(id) => VarInt::write_variant(&(id + 1), write),
+            Self::Inline(value) => {
+                VarInt::write_variant(&0, write)?;
+                value.write(write)
+            }
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for IdSet<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        // Ids are written with their count increased by one and zero means that the tag name is following
+        Ok(match VarInt::read_variant(read)? {
+            0 => Self::Tag(Identifier::read(read)?),
+            length => {
+                let length: i32 = length - 1;
+                let mut ids = Vec::with_capacity(length.clamp(0, 64) as usize);
+                for _ in 0..length {
+                    ids.push(VarInt::read_variant(read)?);
+                }
+                Self::Ids(ids)
+            }
+        })
+    }
+}
+
+impl PacketWritable for IdSet<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        match self {
+            Self::Tag(tag) => {
+                VarInt::write_variant(&0, write)?;
+                tag.write(write)
+            }
+            Self::Ids(ids) => {
+                VarInt::write_variant(&(ids.len() as i32 + 1), write)?;
+                ids.iter().try_for_each(|id| VarInt::write_variant(id, write))
+            }
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for PropertyValue<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Ok(match bool::read(read)? {
+            true => Self::Exact(Cow::read(read)?),
+            false => Self::Range { min: Option::read(read)?, max: Option::read(read)? },
+        })
+    }
+}
+
+impl PacketWritable for PropertyValue<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        match self {
+            Self::Exact(value) => {
+                true.write(write)?;
+                value.write(write)
+            }
+            Self::Range { min, max } => {
+                false.write(write)?;
+                min.write(write)?;
+                max.write(write)
+            }
+        }
+    }
+}
+
+/// Fails if nesting is deeper than [MAX_NESTING_DEPTH], so the peer can not overflow the stack of the reader.
+fn check_depth(depth: usize) -> Result<(), PacketReadableError> {
+    match depth > MAX_NESTING_DEPTH {
+        true => Err(PacketReadableError::Any(anyhow::anyhow!("Nesting is deeper than {}", MAX_NESTING_DEPTH))),
+        false => Ok(()),
+    }
+}
+
+fn read_effect_details<'a, R>(read: &mut R, depth: usize) -> Result<PotionEffectDetails, PacketReadableError>
+    where R: PacketRead<'a> {
+    check_depth(depth)?;
+    Ok(PotionEffectDetails {
+        amplifier: VarInt::read_variant(read)?,
+        duration: VarInt::read_variant(read)?,
+        ambient: bool::read(read)?,
+        show_particles: bool::read(read)?,
+        show_icon: bool::read(read)?,
+        hidden_effect: match bool::read(read)? {
+            true => Some(Box::new(read_effect_details(read, depth + 1)?)),
+            false => None,
+        },
+    })
+}
+
+impl<'a> PacketReadable<'a> for PotionEffectDetails {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        read_effect_details(read, 0)
+    }
+}
+
+impl PacketWritable for PotionEffectDetails {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.amplifier, write)?;
+        VarInt::write_variant(&self.duration, write)?;
+        self.ambient.write(write)?;
+        self.show_particles.write(write)?;
+        self.show_icon.write(write)?;
+        match self.hidden_effect {
+            Some(ref hidden_effect) => {
+                true.write(write)?;
+                hidden_effect.write(write)
+            }
+            None => false.write(write),
+        }
+    }
+}
+
+fn read_nested_slots<'a, R>(read: &mut R, depth: usize) -> Result<Vec<Slot<'a>>, PacketReadableError>
+    where R: PacketRead<'a> {
+    read_list(read, |read| Slot::read_nested(read, i32::MAX, depth + 1))
+}
+
+impl<'a> ItemComponent<'a> {
+    /// Reads component of the slot with the given depth, slots inside of the component are one level deeper.
+    fn read_nested<R>(read: &mut R, depth: usize) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        let id = VarInt::read_variant(read)?;
+        Ok(match id {
+            0 => Self::CustomData(ProtocolNetworkNbt::read_variant(read)?),
+            1 => Self::MaxStackSize(VarInt::read_variant(read)?),
+            2 => Self::MaxDamage(VarInt::read_variant(read)?),
+            3 => Self::Damage(VarInt::read_variant(read)?),
+            4 => Self::Unbreakable { show_in_tooltip: bool::read(read)? },
+            5 => Self::CustomName(ProtocolNbtComponent::read_variant(read)?),
+            6 => Self::ItemName(ProtocolNbtComponent::read_variant(read)?),
+            7 => Self::Lore(read_list(read, ProtocolNbtComponent::read_variant)?),
+            8 => Self::Rarity(Rarity::read(read)?),
+            9 => {
+                let (enchantments, show_in_tooltip) = read_enchantments(read)?;
+                Self::Enchantments { enchantments, show_in_tooltip }
+            }
+            10 => {
+                let (predicates, show_in_tooltip) = read_predicates(read)?;
+                Self::CanPlaceOn { predicates, show_in_tooltip }
+            }
+            11 => {
+                let (predicates, show_in_tooltip) = read_predicates(read)?;
+                Self::CanBreak { predicates, show_in_tooltip }
+            }
+            12 => Self::AttributeModifiers {
+                modifiers: read_list(read, AttributeModifier::read)?,
+                show_in_tooltip: bool::read(read)?,
+            },
+            13 => Self::CustomModelData(VarInt::read_variant(read)?),
+            14 => Self::HideAdditionalTooltip,
+            15 => Self::HideTooltip,
+            16 => Self::RepairCost(VarInt::read_variant(read)?),
+            17 => Self::CreativeSlotLock,
+            18 => Self::EnchantmentGlintOverride(bool::read(read)?),
+            19 => {
+                let _: fastnbt::Value = ProtocolNetworkNbt::read_variant(read)?;
+                Self::IntangibleProjectile
+            }
+            20 => Self::Food(Food::read(read)?),
+            21 => Self::FireResistant,
+            22 => Self::Tool(Tool::read(read)?),
+            23 => {
+                let (enchantments, show_in_tooltip) = read_enchantments(read)?;
+                Self::StoredEnchantments { enchantments, show_in_tooltip }
+            }
+            24 => Self::DyedColor { color: i32::read(read)?, show_in_tooltip: bool::read(read)? },
+            25 => Self::MapColor(i32::read(read)?),
+            26 => Self::MapId(VarInt::read_variant(read)?),
+            27 => Self::MapDecorations(ProtocolNetworkNbt::read_variant(read)?),
+            28 => Self::MapPostProcessing(VarInt::read_variant(read)?),
+            29 => Self::ChargedProjectiles(read_nested_slots(read, depth)?),
+            30 => Self::BundleContents(read_nested_slots(read, depth)?),
+            31 => Self::PotionContents(PotionContents::read(read)?),
+            32 => Self::SuspiciousStewEffects(read_list(read, SuspiciousStewEffect::read)?),
+            33 => Self::WritableBookContent { pages: read_list(read, FilterableText::read)? },
+            34 => Self::WrittenBookContent(WrittenBookContent::read(read)?),
+            35 => Self::Trim(Box::new(ArmorTrim::read(read)?)),
+            36 => Self::DebugStickState(ProtocolNetworkNbt::read_variant(read)?),
+            37 => Self::EntityData(ProtocolNetworkNbt::read_variant(read)?),
+            38 => Self::BucketEntityData(ProtocolNetworkNbt::read_variant(read)?),
+            39 => Self::BlockEntityData(ProtocolNetworkNbt::read_variant(read)?),
+            40 => Self::Instrument(Holder::read(read)?),
+            41 => Self::OminousBottleAmplifier(VarInt::read_variant(read)?),
+            42 => Self::Recipes(read_nbt_string_list(read)?),
+            43 => Self::LodestoneTracker { target: Option::read(read)?, tracked: bool::read(read)? },
+            44 => Self::FireworkExplosion(FireworkExplosion::read(read)?),
+            45 => Self::Fireworks {
+                flight_duration: VarInt::read_variant(read)?,
+                explosions: read_list(read, FireworkExplosion::read)?,
+            },
+            46 => Self::Profile(GameProfile::read(read)?),
+            47 => Self::NoteBlockSound(Identifier::read(read)?),
+            48 => Self::BannerPatterns(read_list(read, BannerPatternLayer::read)?),
+            49 => Self::BaseColor(VarInt::read_variant(read)?),
+            50 => Self::PotDecorations(read_list(read, VarInt::read_variant)?),
+            51 => Self::Container(read_nested_slots(read, depth)?),
+            52 => Self::BlockState(read_list(read, <(Cow<str>, Cow<str>)>::read)?),
+            53 => Self::Bees(read_list(read, Bee::read)?),
+            54 => {
+                expect_tag(read, STRING_TAG)?;
+                Self::Lock(read_nbt_string(read)?)
+            }
+            55 => Self::ContainerLoot(ProtocolNetworkNbt::read_variant(read)?),
+            _ => return Err(PacketReadableError::Any(
+                anyhow::anyhow!("Item component {} is not supported and can not be skipped", id)
+            )),
+        })
+    }
+}
+
+impl<'a> PacketReadable<'a> for ItemComponent<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Self::read_nested(read, 0)
+    }
+}
+
+impl PacketWritable for ItemComponent<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.id(), write)?;
+        match self {
+            Self::CustomData(value) | Self::MapDecorations(value) | Self::DebugStickState(value) |
+            Self::EntityData(value) | Self::BucketEntityData(value) | Self::BlockEntityData(value) |
+            Self::ContainerLoot(value) => ProtocolNetworkNbt::write_variant(value, write),
+            Self::MaxStackSize(value) | Self::MaxDamage(value) | Self::Damage(value) | Self::CustomModelData(value) |
+            Self::RepairCost(value) | Self::MapId(value) | Self::MapPostProcessing(value) |
+            Self::OminousBottleAmplifier(value) | Self::BaseColor(value) => VarInt::write_variant(value, write),
+            Self::Unbreakable { show_in_tooltip } => show_in_tooltip.write(write),
+            Self::CustomName(name) | Self::ItemName(name) => ProtocolNbtComponent::write_variant(name, write),
+            Self::Lore(lines) => write_list(lines, write, ProtocolNbtComponent::write_variant),
+            Self::Rarity(rarity) => rarity.write(write),
+            Self::Enchantments { enchantments, show_in_tooltip } |
+            Self::StoredEnchantments { enchantments, show_in_tooltip } =>
+                write_enchantments(enchantments, *show_in_tooltip, write),
+            Self::CanPlaceOn { predicates, show_in_tooltip } | Self::CanBreak { predicates, show_in_tooltip } => {
+                write_list(predicates, write, |predicate, write| predicate.write(write))?;
+                show_in_tooltip.write(write)
+            }
+            Self::AttributeModifiers { modifiers, show_in_tooltip } => {
+                write_list(modifiers, write, |modifier, write| modifier.write(write))?;
+                show_in_tooltip.write(write)
+            }
+            Self::HideAdditionalTooltip | Self::HideTooltip | Self::CreativeSlotLock | Self::FireResistant => Ok(()),
+            Self::EnchantmentGlintOverride(value) => value.write(write),
+            Self::IntangibleProjectile =>
+                ProtocolNetworkNbt::write_variant(&fastnbt::Value::Compound(Default::default()), write),
+            Self::Food(food) => food.write(write),
+            Self::Tool(tool) => tool.write(write),
+            Self::DyedColor { color, show_in_tooltip } => {
+                color.write(write)?;
+                show_in_tooltip.write(write)
+            }
+            Self::MapColor(color) => color.write(write),
+            Self::ChargedProjectiles(slots) | Self::BundleContents(slots) | Self::Container(slots) =>
+                write_list(slots, write, |slot, write| slot.write(write)),
+            Self::PotionContents(contents) => contents.write(write),
+            Self::SuspiciousStewEffects(effects) => write_list(effects, write, |effect, write| effect.write(write)),
+            Self::WritableBookContent { pages } => write_list(pages, write, |page, write| page.write(write)),
+            Self::WrittenBookContent(content) => content.write(write),
+            Self::Trim(trim) => trim.write(write),
+            Self::Instrument(instrument) => instrument.write(write),
+            Self::Recipes(recipes) => write_nbt_string_list(recipes, write),
+            Self::LodestoneTracker { target, tracked } => {
+                target.write(write)?;
+                tracked.write(write)
+            }
+            Self::FireworkExplosion(explosion) => explosion.write(write),
+            Self::Fireworks { flight_duration, explosions } => {
+                VarInt::write_variant(flight_duration, write)?;
+                write_list(explosions, write, |explosion, write| explosion.write(write))
+            }
+            Self::Profile(profile) => profile.write(write),
+            Self::NoteBlockSound(sound) => sound.write(write),
+            Self::BannerPatterns(layers) => write_list(layers, write, |layer, write| layer.write(write)),
+            Self::PotDecorations(items) => write_list(items, write, VarInt::write_variant),
+            Self::BlockState(properties) => write_list(properties, write, |property, write| property.write(write)),
+            Self::Bees(bees) => write_list(bees, write, |bee, write| bee.write(write)),
+            Self::Lock(lock) => {
+                write.write_byte(STRING_TAG)?;
+                write_nbt_string(lock, write)
+            }
+            Self::Raw { data, .. } => write.write_bytes(data),
+        }
+    }
+}
+
+fn read_legacy_nbt<'a, R>(read: &mut R, version: i32) -> Result<Option<fastnbt::Value>, PacketReadableError>
+    where R: PacketRead<'a> {
+    if version >= ProtocolVersion::V1_20_2.protocol() {
+        return ProtocolOptionalNetworkNbt::read_variant(read);
+    }
+    if read.take_byte()? == 0 {
+        return Ok(None);
+    }
+    read.rollback(1)?;
+    ProtocolNbt::read_variant(read).map(Some)
+}
+
+fn write_legacy_nbt<W>(nbt: &Option<fastnbt::Value>, write: &mut W, version: i32) -> Result<(), Error>
+    where W: PacketWrite {
+    match (nbt, version >= ProtocolVersion::V1_20_2.protocol()) {
+        (nbt, true) => ProtocolOptionalNetworkNbt::write_variant(nbt, write),
+        (Some(nbt), false) => ProtocolNbt::write_variant(nbt, write),
+        (None, false) => write.write_byte(0),
+    }
+}
+
+impl<'a> Slot<'a> {
+    /// Reads slot which is nested by the components of the given depth of slots, zero is the top level slot.
+    fn read_nested<R>(read: &mut R, version: i32, depth: usize) -> Result<Self, PacketReadableError>
+        where R: PacketRead<'a> {
+        check_depth(depth)?;
+        if version < ProtocolVersion::V1_20_5.protocol() {
+            return Ok(Self(match bool::read(read)? {
+                true => {
+                    let item_id = VarInt::read_variant(read)?;
+                    let count = i8::read(read)? as i32;
+                    let mut item = ItemStack::new(item_id, count);
+                    item.nbt = read_legacy_nbt(read, version)?;
+                    Some(item)
+                }
+                false => None,
+            }));
+        }
+        let count: i32 = VarInt::read_variant(read)?;
+        if count <= 0 {
+            return Ok(Self(None));
+        }
+        let item_id = VarInt::read_variant(read)?;
+        let added: i32 = VarInt::read_variant(read)?;
+        let removed: i32 = VarInt::read_variant(read)?;
+        let mut item = ItemStack::new(item_id, count);
+        for _ in 0..added {
+            item.components.push(ItemComponent::read_nested(read, depth)?);
+        }
+        for _ in 0..removed {
+            item.removed_components.push(VarInt::read_variant(read)?);
+        }
+        Ok(Self(Some(item)))
+    }
+}
+
+impl<'a> PacketVersionedReadable<'a> for Slot<'a> {
+    fn read_versioned<R>(read: &mut R, version: i32) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Self::read_nested(read, version, 0)
+    }
+}
+
+impl PacketVersionedWritable for Slot<'_> {
+    fn write_versioned<W>(&self, write: &mut W, version: i32) -> Result<(), Error> where W: PacketWrite {
+        if version < ProtocolVersion::V1_20_5.protocol() {
+            return match self.0 {
+                Some(ref item) => {
+                    if !item.components.is_empty() || !item.removed_components.is_empty() {
+                        return Err(Error::msg("Item components can not be written before 1.20.5"));
+                    }
+                    true.write(write)?;
+                    VarInt::write_variant(&item.item_id, write)?;
+                    i8::try_from(item.count)?.write(write)?;
+                    write_legacy_nbt(&item.nbt, write, version)
+                }
+                None => false.write(write),
+            };
+        }
+        match self.0 {
+            Some(ref item) if item.count > 0 => {
+                if item.nbt.is_some() {
+                    return Err(Error::msg("Item nbt can not be written since 1.20.5, use components instead"));
+                }
+                VarInt::write_variant(&item.count, write)?;
+                VarInt::write_variant(&item.item_id, write)?;
+                VarInt::write_variant(&(item.components.len() as i32), write)?;
+                VarInt::write_variant(&(item.removed_components.len() as i32), write)?;
+                item.components.iter().try_for_each(|component| component.write(write))?;
+                item.removed_components.iter().try_for_each(|id| VarInt::write_variant(id, write))
+            }
+            _ => VarInt::write_variant(&0, write),
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for Slot<'a> {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Self::read_versioned(read, i32::MAX)
+    }
+}
+
+impl PacketWritable for Slot<'_> {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        self.write_versioned(write, i32::MAX)
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::packet::{Packet, PacketWritable};
+use crate::packet_frame::{PacketFrame, PacketFrameBuf};
+use crate::packet_pipeline::PacketPipeline;
+
+const READ_BUFFER_SIZE: usize = 1024;
+
+/// Reads bytes from [tokio::io::AsyncRead] until the whole frame is received.
+///
+/// Bytes that are read after the frame stay in the pipeline and are used by the next call.
+pub async fn read_frame<R>(read: &mut R, pipeline: &mut PacketPipeline) -> Result<PacketFrameBuf, Error>
+    where R: AsyncRead + Unpin {
+    let mut buffer = [0u8; READ_BUFFER_SIZE];
+    loop {
+        if let Some(frame) = pipeline.next_frame()? {
+            break Ok(frame);
+        }
+        match read.read(&mut buffer).await? {
+            0 => break Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+            length => pipeline.receive(&buffer[..length]),
+        }
+    }
+}
+
+pub async fn write_packet<T, W>(write: &mut W, pipeline: &mut PacketPipeline, packet: &T) -> Result<(), Error>
+    where T: Packet + PacketWritable, W: AsyncWrite + Unpin {
+    let mut bytes = Vec::new();
+    pipeline.write_packet(packet, &mut bytes)?;
+    Ok(write.write_all(&bytes).await?)
+}
+
+pub async fn write_frame<W>(write: &mut W, pipeline: &mut PacketPipeline, frame: PacketFrame<'_>) -> Result<(), Error>
+    where W: AsyncWrite + Unpin {
+    let mut data = Vec::with_capacity(frame.body.len() + 5);
+    frame.write_data(&mut data)?;
+    let mut bytes = Vec::new();
+    pipeline.write_data(&data, &mut bytes)?;
+    Ok(write.write_all(&bytes).await?)
+}
@@ -39,13 +39,40 @@ pub struct ProtocolJson;
 
 pub struct ProtocolNbt;
 
+/// Nbt without root compound name, which is sent since 1.20.2.
+pub struct ProtocolNetworkNbt;
+
+/// [ProtocolNetworkNbt] which is [None] if only the end tag is sent.
+pub struct ProtocolOptionalNetworkNbt;
+
+/// Text component which is sent as network nbt since 1.20.3.
+pub struct ProtocolNbtComponent;
+
+/// [Option] which value is written with the given variant. Presence is written as [bool] before value.
+pub struct OptionVariant<
+    Value,
+    ValueInner = Value,
+>(
+    PhantomData<Value>, PhantomData<ValueInner>,
+);
+
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct BlockPosition {
     pub x: i32,
     pub y: i16,
     pub z: i32,
 }
 
+/// Position of the player or entity. Written as three [f64].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EntityPosition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Rotation angle in radians which is written as a byte, a full turn is 256 steps.
 pub struct Angle;
 
 /// Packet variant for PacketWritable and PacketReadable.
@@ -156,6 +183,31 @@ impl PacketWritable for Cow<'_, str> {
     }
 }
 
+impl<'a, const N: usize> PacketReadable<'a> for &'a [u8; N] {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        // Panics. never take_slice returns slice of the given length
+        Ok(read.take_slice(N)?.try_into().unwrap())
+    }
+}
+
+impl<'a, const N: usize> PacketReadable<'a> for [u8; N] {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        <&'a [u8; N]>::read(read).copied()
+    }
+}
+
+impl<const N: usize> PacketWritable for [u8; N] {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        write.write_bytes(self)
+    }
+}
+
+impl<const N: usize> PacketWritable for &[u8; N] {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        write.write_bytes(*self)
+    }
+}
+
 impl<'a> PacketVariantReadable<'a, &'a [u8]> for RemainingBytesSlice {
     fn read_variant<R>(read: &mut R) -> Result<&'a [u8], PacketReadableError> where R: PacketRead<'a> {
         read.take_slice(read.available())
@@ -328,7 +380,8 @@ impl<
 > PacketVariantReadable<'a, Vec<ValueInner>> for LengthProvidedSlice<Length, Value, LengthInner, ValueInner> {
     fn read_variant<R>(read: &mut R) -> Result<Vec<ValueInner>, PacketReadableError> where R: PacketRead<'a> {
         let length = Length::read_variant(read)?.into_length();
-        let mut result = Vec::with_capacity(length);
+        // Length is not trusted, so vector grows while elements are read
+        let mut result = Vec::with_capacity(length.min(64));
         for _ in 0..length {
             result.push(Value::read_variant(read)?);
         }
@@ -409,14 +462,14 @@ impl<T: serde::Serialize> PacketVariantWritable<T> for ProtocolJson {
 
 impl<'a> PacketVariantReadable<'a, f32> for Angle {
     fn read_variant<R>(read: &mut R) -> Result<f32, PacketReadableError> where R: PacketRead<'a> {
-        Ok(u8::read(read)? as f32 * std::f32::consts::PI / 256f32)
+        Ok(u8::read(read)? as f32 * std::f32::consts::TAU / 256f32)
     }
 }
 
 impl PacketVariantWritable<f32> for Angle {
     fn write_variant<W>(object: &f32, write: &mut W) -> Result<(), Error> where W: PacketWrite {
-        // TODO should we check for panic situations?
-        ((*object * 256f32 / std::f32::consts::PI) as u8).write(write)
+        // Full turn is 256 steps, so the angle is wrapped through i32 to keep negative angles
+        ((*object * 256f32 / std::f32::consts::TAU).round() as i32 as u8).write(write)
     }
 }
 
@@ -449,6 +502,20 @@ impl PacketWritable for BlockPosition {
     }
 }
 
+impl<'a> PacketReadable<'a> for EntityPosition {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Ok(EntityPosition { x: f64::read(read)?, y: f64::read(read)?, z: f64::read(read)? })
+    }
+}
+
+impl PacketWritable for EntityPosition {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        self.x.write(write)?;
+        self.y.write(write)?;
+        self.z.write(write)
+    }
+}
+
 impl<'a> PacketReadable<'a> for bird_chat::component::Component<'a> {
     fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
         let bytes = read_bytes_with_limit(read, CHAT_LIMIT)?;
@@ -526,6 +593,34 @@ impl<'a, T: PacketReadable<'a>> PacketReadable<'a> for Option<T> {
     }
 }
 
+impl<
+    'a,
+    Value: PacketVariantReadable<'a, ValueInner>,
+    ValueInner
+> PacketVariantReadable<'a, Option<ValueInner>> for OptionVariant<Value, ValueInner> {
+    fn read_variant<R>(read: &mut R) -> Result<Option<ValueInner>, PacketReadableError> where R: PacketRead<'a> {
+        Ok(match bool::read(read)? {
+            true => Some(Value::read_variant(read)?),
+            false => None
+        })
+    }
+}
+
+impl<
+    Value: PacketVariantWritable<ValueInner>,
+    ValueInner
+> PacketVariantWritable<Option<ValueInner>> for OptionVariant<Value, ValueInner> {
+    fn write_variant<W>(object: &Option<ValueInner>, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        match object {
+            Some(ref obj) => {
+                true.write(write)?;
+                Value::write_variant(obj, write)
+            }
+            None => false.write(write)
+        }
+    }
+}
+
 macro_rules! length_impl {
     ($num: ident) => {
         impl const PacketLength for $num {
@@ -543,6 +638,41 @@ macro_rules! length_impl {
     }
 }
 
+macro_rules! tuple_impl {
+    ($($ty: ident),*) => {
+        impl<'a, $($ty: PacketReadable<'a>),*> PacketReadable<'a> for ($($ty,)*) {
+            fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+                Ok(($($ty::read(read)?,)*))
+            }
+        }
+
+        impl<$($ty: PacketWritable),*> PacketWritable for ($($ty,)*) {
+            #[allow(non_snake_case)]
+            fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+                let ($(ref $ty,)*) = self;
+                $($ty.write(write)?;)*
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! float_impl {
+    ($float: ident, $bits: ident) => {
+        impl<'a> PacketReadable<'a> for $float {
+            fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+                $bits::read(read).map($float::from_bits)
+            }
+        }
+
+        impl PacketWritable for $float {
+            fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+                self.to_bits().write(write)
+            }
+        }
+    }
+}
+
 macro_rules! number_impl {
     ($num: ident) => {
         impl<'a> PacketReadable<'a> for $num {
@@ -609,4 +739,8 @@ macro_rules! var_number_impl {
 length_impl!(u8 i8 u16 i16 u32 i32 u64 i64);
 number_impl!(u16 i16 u32 i32 u64 i64 u128 i128);
 var_number_impl!(VarInt, i32, u32);
-var_number_impl!(VarLong, i64, u64);
\ No newline at end of file
+var_number_impl!(VarLong, i64, u64);
+float_impl!(f32, u32);
+float_impl!(f64, u64);
+tuple_impl!(A, B);
+tuple_impl!(A, B, C);
\ No newline at end of file
@@ -0,0 +1,221 @@
+use std::fmt::{Display, Formatter};
+use anyhow::Error;
+use crate::packet::{PacketRead, PacketReadable, PacketReadableError, PacketVariantReadable, PacketVariantWritable, PacketWritable, PacketWrite};
+use crate::packet_types::VarInt;
+
+/// Protocol version number which is sent in the handshake packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion(pub i32);
+
+/// Known releases and their protocol versions in the release order.
+const RELEASES: &[(&str, ProtocolVersion)] = &[
+    ("1.8", ProtocolVersion::V1_8),
+    ("1.8.1", ProtocolVersion::V1_8),
+    ("1.8.2", ProtocolVersion::V1_8),
+    ("1.8.3", ProtocolVersion::V1_8),
+    ("1.8.4", ProtocolVersion::V1_8),
+    ("1.8.5", ProtocolVersion::V1_8),
+    ("1.8.6", ProtocolVersion::V1_8),
+    ("1.8.7", ProtocolVersion::V1_8),
+    ("1.8.8", ProtocolVersion::V1_8),
+    ("1.8.9", ProtocolVersion::V1_8),
+    ("1.9", ProtocolVersion::V1_9),
+    ("1.9.1", ProtocolVersion::V1_9_1),
+    ("1.9.2", ProtocolVersion::V1_9_2),
+    ("1.9.3", ProtocolVersion::V1_9_3),
+    ("1.9.4", ProtocolVersion::V1_9_3),
+    ("1.10", ProtocolVersion::V1_10),
+    ("1.10.1", ProtocolVersion::V1_10),
+    ("1.10.2", ProtocolVersion::V1_10),
+    ("1.11", ProtocolVersion::V1_11),
+    ("1.11.1", ProtocolVersion::V1_11_1),
+    ("1.11.2", ProtocolVersion::V1_11_1),
+    ("1.12", ProtocolVersion::V1_12),
+    ("1.12.1", ProtocolVersion::V1_12_1),
+    ("1.12.2", ProtocolVersion::V1_12_2),
+    ("1.13", ProtocolVersion::V1_13),
+    ("1.13.1", ProtocolVersion::V1_13_1),
+    ("1.13.2", ProtocolVersion::V1_13_2),
+    ("1.14", ProtocolVersion::V1_14),
+    ("1.14.1", ProtocolVersion::V1_14_1),
+    ("1.14.2", ProtocolVersion::V1_14_2),
+    ("1.14.3", ProtocolVersion::V1_14_3),
+    ("1.14.4", ProtocolVersion::V1_14_4),
+    ("1.15", ProtocolVersion::V1_15),
+    ("1.15.1", ProtocolVersion::V1_15_1),
+    ("1.15.2", ProtocolVersion::V1_15_2),
+    ("1.16", ProtocolVersion::V1_16),
+    ("1.16.1", ProtocolVersion::V1_16_1),
+    ("1.16.2", ProtocolVersion::V1_16_2),
+    ("1.16.3", ProtocolVersion::V1_16_3),
+    ("1.16.4", ProtocolVersion::V1_16_4),
+    ("1.16.5", ProtocolVersion::V1_16_4),
+    ("1.17", ProtocolVersion::V1_17),
+    ("1.17.1", ProtocolVersion::V1_17_1),
+    ("1.18", ProtocolVersion::V1_18),
+    ("1.18.1", ProtocolVersion::V1_18),
+    ("1.18.2", ProtocolVersion::V1_18_2),
+    ("1.19", ProtocolVersion::V1_19),
+    ("1.19.1", ProtocolVersion::V1_19_1),
+    ("1.19.2", ProtocolVersion::V1_19_1),
+    ("1.19.3", ProtocolVersion::V1_19_3),
+    ("1.19.4", ProtocolVersion::V1_19_4),
+    ("1.20", ProtocolVersion::V1_20),
+    ("1.20.1", ProtocolVersion::V1_20),
+    ("1.20.2", ProtocolVersion::V1_20_2),
+    ("1.20.3", ProtocolVersion::V1_20_3),
+    ("1.20.4", ProtocolVersion::V1_20_3),
+    ("1.20.5", ProtocolVersion::V1_20_5),
+    ("1.20.6", ProtocolVersion::V1_20_5),
+    ("1.21", ProtocolVersion::V1_21),
+    ("1.21.1", ProtocolVersion::V1_21),
+    ("1.21.2", ProtocolVersion::V1_21_2),
+    ("1.21.3", ProtocolVersion::V1_21_2),
+    ("1.21.4", ProtocolVersion::V1_21_4),
+];
+
+impl ProtocolVersion {
+    pub const V1_8: Self = Self(47);
+    pub const V1_9: Self = Self(107);
+    pub const V1_9_1: Self = Self(108);
+    pub const V1_9_2: Self = Self(109);
+    pub const V1_9_3: Self = Self(110);
+    pub const V1_10: Self = Self(210);
+    pub const V1_11: Self = Self(315);
+    pub const V1_11_1: Self = Self(316);
+    pub const V1_12: Self = Self(335);
+    pub const V1_12_1: Self = Self(338);
+    pub const V1_12_2: Self = Self(340);
+    pub const V1_13: Self = Self(393);
+    pub const V1_13_1: Self = Self(401);
+    pub const V1_13_2: Self = Self(404);
+    pub const V1_14: Self = Self(477);
+    pub const V1_14_1: Self = Self(480);
+    pub const V1_14_2: Self = Self(485);
+    pub const V1_14_3: Self = Self(490);
+    pub const V1_14_4: Self = Self(498);
+    pub const V1_15: Self = Self(573);
+    pub const V1_15_1: Self = Self(575);
+    pub const V1_15_2: Self = Self(578);
+    pub const V1_16: Self = Self(735);
+    pub const V1_16_1: Self = Self(736);
+    pub const V1_16_2: Self = Self(751);
+    pub const V1_16_3: Self = Self(753);
+    pub const V1_16_4: Self = Self(754);
+    pub const V1_17: Self = Self(755);
+    pub const V1_17_1: Self = Self(756);
+    pub const V1_18: Self = Self(757);
+    pub const V1_18_2: Self = Self(758);
+    pub const V1_19: Self = Self(759);
+    pub const V1_19_1: Self = Self(760);
+    pub const V1_19_3: Self = Self(761);
+    pub const V1_19_4: Self = Self(762);
+    pub const V1_20: Self = Self(763);
+    pub const V1_20_2: Self = Self(764);
+    pub const V1_20_3: Self = Self(765);
+    pub const V1_20_5: Self = Self(766);
+    pub const V1_21: Self = Self(767);
+    pub const V1_21_2: Self = Self(768);
+    pub const V1_21_4: Self = Self(769);
+
+    pub const OLDEST: Self = Self::V1_8;
+    pub const LATEST: Self = Self::V1_21_4;
+
+    pub const fn new(protocol: i32) -> Self {
+        Self(protocol)
+    }
+
+    pub const fn protocol(self) -> i32 {
+        self.0
+    }
+
+    /// Finds protocol version of the release, for example `"1.19.2"` is 760.
+    pub fn from_release(release: &str) -> Option<Self> {
+        RELEASES.iter()
+            .find(|(name, _)| *name == release)
+            .map(|(_, version)| *version)
+    }
+
+    /// Returns all known protocol versions from the oldest to the latest.
+    pub fn known() -> impl Iterator<Item=Self> {
+        RELEASES.iter()
+            .enumerate()
+            .filter(|(index, (_, version))| *index == 0 || RELEASES[index - 1].1 != *version)
+            .map(|(_, (_, version))| *version)
+    }
+
+    pub fn is_known(self) -> bool {
+        RELEASES.iter().any(|(_, version)| *version == self)
+    }
+
+    /// Returns names of all releases which are using this protocol version.
+    pub fn releases(self) -> impl Iterator<Item=&'static str> {
+        RELEASES.iter()
+            .filter(move |(_, version)| *version == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// Returns name of the latest release which is using this protocol version.
+    pub fn name(self) -> Option<&'static str> {
+        self.releases().last()
+    }
+
+    /// Chat messages are signed by players since 1.19.
+    pub fn has_chat_signing(self) -> bool {
+        self >= Self::V1_19
+    }
+
+    /// Configuration state is between login and play states since 1.20.2.
+    pub fn has_configuration_state(self) -> bool {
+        self >= Self::V1_20_2
+    }
+
+    /// Nbt sent over the network has no root compound name since 1.20.2.
+    pub fn has_network_nbt(self) -> bool {
+        self >= Self::V1_20_2
+    }
+
+    /// Text components are sent as nbt instead of json since 1.20.3.
+    pub fn has_nbt_text_components(self) -> bool {
+        self >= Self::V1_20_3
+    }
+}
+
+impl From<i32> for ProtocolVersion {
+    fn from(protocol: i32) -> Self {
+        Self(protocol)
+    }
+}
+
+impl From<ProtocolVersion> for i32 {
+    fn from(version: ProtocolVersion) -> Self {
+        version.0
+    }
+}
+
+impl PartialEq<i32> for ProtocolVersion {
+    fn eq(&self, other: &i32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{} ({})", name, self.0),
+            None => write!(f, "unknown ({})", self.0),
+        }
+    }
+}
+
+impl<'a> PacketReadable<'a> for ProtocolVersion {
+    fn read<R>(read: &mut R) -> Result<Self, PacketReadableError> where R: PacketRead<'a> {
+        Ok(Self(VarInt::read_variant(read)?))
+    }
+}
+
+impl PacketWritable for ProtocolVersion {
+    fn write<W>(&self, write: &mut W) -> Result<(), Error> where W: PacketWrite {
+        VarInt::write_variant(&self.0, write)
+    }
+}
@@ -2,6 +2,9 @@ use std::borrow::Cow;
 use anyhow::Error;
 use crate::packet::*;
 use crate::packet_types::*;
+use crate::packet_frame::*;
+use crate::packet_set::*;
+use crate::packet_version::*;
 
 #[test]
 fn number_tests() {
@@ -69,4 +72,1659 @@ fn slice_tests() {
             &["hello", "bye", "yeah"]
         );
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn frame_tests() {
+    {
+        let mut write = Vec::new();
+        write_frame(&[0x00, 0x01, 0x02], &mut write).unwrap();
+        write_frame(&[0x03], &mut write).unwrap();
+        assert_eq!(write, &[0x03, 0x00, 0x01, 0x02, 0x01, 0x03]);
+        let mut frames = Frames::new(&write[..5]);
+        assert_eq!(frames.next().unwrap().unwrap(), &[0x00, 0x01, 0x02]);
+        assert_eq!(frames.next().is_none(), true);
+        assert_eq!(frames.consumed(), 4);
+        assert_eq!(frames.remaining(), &[0x01]);
+
+        let mut write = vec![0x01, 0x07];
+        write.extend_from_slice(&[0xff, 0xff, 0xff, 0x01, 0x00]);
+        let mut frames = Frames::new(&write);
+        assert_eq!(frames.next().unwrap().unwrap(), &[0x07]);
+        assert_eq!(frames.next().unwrap().is_err(), true);
+        assert_eq!(frames.next().is_none(), true);
+        assert_eq!(frames.next().is_none(), true);
+        assert_eq!(frames.consumed(), 2);
+    }
+    {
+        assert_eq!(read_frame(&[]).unwrap(), FrameRead::NeedMore);
+        assert_eq!(read_frame(&[0x80]).unwrap(), FrameRead::NeedMore);
+        assert_eq!(read_frame(&[0x80, 0x01, 0x00]).unwrap(), FrameRead::NeedMore);
+        assert_eq!(read_frame(&[0xff, 0xff, 0xff, 0x01]).is_err(), true);
+        assert_eq!(
+            read_frame(&[0x02, 0x01, 0x05, 0x07]).unwrap(),
+            FrameRead::Frame { frame: &[0x01, 0x05], consumed: 3 }
+        );
+        let frame = PacketFrame::new(&[0x01, 0x05]).unwrap();
+        assert_eq!(frame, PacketFrame { id: 1, body: &[0x05] });
+    }
+}
+
+#[cfg(feature = "fastnbt")]
+#[test]
+fn nbt_length_tests() {
+    let value = fastnbt::nbt!({
+        "name": "jenya705",
+        "compounds": [{ "text": "a", "bold": 1i8 }, { "text": "bc", "extra": ["d", "e"] }],
+        "strings": ["first", "second", "third"],
+        "lists": [[1, 2], ["f"]],
+        "ints": fastnbt::IntArray::new(vec![1, 2, 3]),
+        "bytes": fastnbt::ByteArray::new(vec![4, 5]),
+    });
+    let mut write = Vec::new();
+    ProtocolNbt::write_variant(&value, &mut write).unwrap();
+    ProtocolNetworkNbt::write_variant(&value, &mut write).unwrap();
+    write.push(0xAB);
+    // Every byte must be counted once, so the trailing byte is left after both nbt
+    let mut read = SlicePacketRead::new(&write);
+    assert_eq!(<ProtocolNbt as PacketVariantReadable<fastnbt::Value>>::read_variant(&mut read).unwrap(), value);
+    assert_eq!(<ProtocolNetworkNbt as PacketVariantReadable<fastnbt::Value>>::read_variant(&mut read).unwrap(), value);
+    assert_eq!(read.available(), 1);
+
+    // Compounds with the empty names nested into each other
+    let nested = |depth: usize| {
+        let mut bytes = vec![10];
+        (1..depth).for_each(|_| bytes.extend_from_slice(&[10, 0, 0]));
+        bytes.resize(bytes.len() + depth, 0);
+        bytes
+    };
+    let read_nested = |bytes: &[u8]| -> Result<fastnbt::Value, PacketReadableError> {
+        ProtocolNetworkNbt::read_variant(&mut SlicePacketRead::new(bytes))
+    };
+    // Debug build of fastnbt needs more stack than the test thread has to deserialize 512 nested compounds
+    let deepest = nested(crate::packet_fastnbt::MAX_NBT_DEPTH);
+    let deepest = std::thread::Builder::new().stack_size(8 << 20)
+        .spawn(move || read_nested(&deepest).is_ok())
+        .unwrap().join().unwrap();
+    assert_eq!(deepest, true);
+    for depth in [crate::packet_fastnbt::MAX_NBT_DEPTH + 1, 300_000] {
+        assert_eq!(read_nested(&nested(depth)).unwrap_err().to_string(), "Nbt is deeper than 512");
+    }
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compression_tests() {
+    use crate::packet_compression::PacketCompression;
+    let compression = PacketCompression::new(4).unwrap();
+    {
+        let mut write = Vec::new();
+        compression.compress(&[0x01, 0x02], &mut write).unwrap();
+        assert_eq!(write, &[0x00, 0x01, 0x02]);
+        assert_eq!(compression.decompress(&write).unwrap().as_ref(), &[0x01, 0x02]);
+    }
+    {
+        let data = [0x07u8; 64];
+        let mut write = Vec::new();
+        compression.compress(&data, &mut write).unwrap();
+        assert_eq!(write[0], 64);
+        assert_eq!(compression.decompress(&write).unwrap().as_ref(), &data);
+        assert_eq!(compression.with_max_data_length(32).decompress(&write).is_err(), true);
+        write[0] = 32;
+        assert_eq!(compression.decompress(&write).is_err(), true);
+    }
+    assert_eq!(compression.decompress(&[0x00, 0x01, 0x02, 0x03, 0x04]).is_err(), true);
+    assert_eq!(PacketCompression::new(-1), None);
+}
+
+#[cfg(all(feature = "compression", feature = "encryption", feature = "packet_default"))]
+#[test]
+fn pipeline_tests() {
+    use crate::packet_compression::PacketCompression;
+    use crate::packet_default::StatusPingRequest;
+    use crate::packet_encryption::PacketCipher;
+    use crate::packet_pipeline::PacketPipeline;
+    let secret = [0x11u8; 16];
+    let mut client = PacketPipeline::new();
+    let mut server = PacketPipeline::new();
+    let mut write = Vec::new();
+    client.write_packet(&StatusPingRequest { payload: 1 }, &mut write).unwrap();
+    client.enable_encryption(PacketCipher::new(&secret).unwrap());
+    client.set_compression(PacketCompression::new(0));
+    client.write_packet(&StatusPingRequest { payload: 2 }, &mut write).unwrap();
+    assert_eq!(PacketCipher::new(&[0u8; 3]).is_err(), true);
+    server.receive(&write[..5]);
+    assert_eq!(server.next_frame().unwrap(), None);
+    server.receive(&write[5..]);
+    let frame = server.next_frame().unwrap().unwrap();
+    assert_eq!(frame.read_packet::<StatusPingRequest>().unwrap().payload, 1);
+    server.enable_encryption(PacketCipher::new(&secret).unwrap());
+    server.set_compression(PacketCompression::new(0));
+    let frame = server.next_frame().unwrap().unwrap();
+    assert_eq!(frame.read_packet::<StatusPingRequest>().unwrap().payload, 2);
+    assert_eq!(server.next_frame().unwrap(), None);
+    assert_eq!(server.buffered(), 0);
+}
+
+#[cfg(all(feature = "tokio-codec", feature = "packet_default"))]
+#[test]
+fn codec_tests() {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+    use crate::packet_codec::PacketCodec;
+    use crate::packet_compression::PacketCompression;
+    use crate::packet_default::StatusPingResponse;
+    let mut encoder = PacketCodec::new();
+    let mut decoder = PacketCodec::new();
+    let mut bytes = BytesMut::new();
+    encoder.set_compression(PacketCompression::new(256));
+    encoder.encode(&StatusPingResponse { payload: 5 }, &mut bytes).unwrap();
+    let mut partial = bytes.split_to(3);
+    assert_eq!(decoder.decode(&mut partial).unwrap(), None);
+    decoder.set_compression(PacketCompression::new(256));
+    assert_eq!(decoder.decode_eof(&mut bytes).unwrap().unwrap().read_packet::<StatusPingResponse>().unwrap().payload, 5);
+    assert_eq!(decoder.decode_eof(&mut bytes).unwrap(), None);
+}
+
+#[cfg(feature = "packet_default")]
+#[test]
+fn io_tests() {
+    use crate::packet_default::StatusPingRequest;
+    use crate::packet_io::{PacketReader, PacketWriter};
+    let mut writer = PacketWriter::new(Vec::new());
+    writer.write_packet(&StatusPingRequest { payload: 1 }).unwrap();
+    writer.write_packet(&StatusPingRequest { payload: 2 }).unwrap();
+    writer.flush().unwrap();
+    let mut reader = PacketReader::with_capacity(2, writer.get_ref().as_slice());
+    assert_eq!(reader.read_frame().unwrap().read_packet::<StatusPingRequest>().unwrap().payload, 1);
+    assert_eq!(reader.read_frame().unwrap().read_packet::<StatusPingRequest>().unwrap().payload, 2);
+    assert_eq!(reader.read_frame().is_err(), true);
+    let mut reader = PacketReader::with_capacity(0, writer.get_ref().as_slice());
+    assert_eq!(reader.read_frame().unwrap().read_packet::<StatusPingRequest>().unwrap().payload, 1);
+}
+
+#[cfg(all(feature = "tokio-io", feature = "futures-io", feature = "packet_default"))]
+#[tokio::test(flavor = "current_thread")]
+async fn async_io_tests() {
+    use crate::packet_default::StatusPingRequest;
+    use crate::packet_pipeline::PacketPipeline;
+    let mut bytes = Vec::new();
+    let mut pipeline = PacketPipeline::new();
+    crate::packet_tokio::write_packet(&mut bytes, &mut pipeline, &StatusPingRequest { payload: 1 }).await.unwrap();
+    crate::packet_futures::write_packet(&mut bytes, &mut pipeline, &StatusPingRequest { payload: 2 }).await.unwrap();
+    let mut read = bytes.as_slice();
+    assert_eq!(
+        crate::packet_tokio::read_frame(&mut read, &mut pipeline).await.unwrap()
+            .read_packet::<StatusPingRequest>().unwrap().payload,
+        1
+    );
+    assert_eq!(
+        crate::packet_futures::read_frame(&mut read, &mut pipeline).await.unwrap()
+            .read_packet::<StatusPingRequest>().unwrap().payload,
+        2
+    );
+    assert_eq!(crate::packet_tokio::read_frame(&mut read, &mut pipeline).await.is_err(), true);
+}
+
+#[cfg(feature = "packet_default")]
+#[test]
+fn connection_tests() {
+    use crate::packet::marker::{Client, Server};
+    use crate::packet_connection::Connection;
+    use crate::packet_default::*;
+    let mut client: Connection<_, Client, _> = Connection::new(Vec::new());
+    client.send(&HandshakePacket {
+        protocol_version: ProtocolVersion::V1_19_1,
+        server_address: "localhost",
+        server_port: 25565,
+        next_state: HandshakeNextState::Status,
+    }).unwrap();
+    let mut client = client.into_status();
+    client.send(&StatusPingRequest { payload: 3 }).unwrap();
+    let mut server: Connection<_, Server, _> = Connection::new(Vec::new());
+    server.receive(client.get_ref());
+    let frame = server.next_frame().unwrap().unwrap();
+    let handshake: HandshakePacket = server.read_packet(frame.as_frame()).unwrap();
+    assert_eq!(handshake.next_state, HandshakeNextState::Status);
+    let mut server = server.into_status();
+    let frame = server.next_frame().unwrap().unwrap();
+    let ping: StatusPingRequest = server.read_packet(frame.as_frame()).unwrap();
+    assert_eq!(ping.payload, 3);
+}
+
+#[cfg(all(feature = "packet_default", feature = "compression", feature = "encryption"))]
+#[test]
+fn engine_tests() {
+    use uuid::Uuid;
+    use crate::packet_default::*;
+    use crate::packet_engine::{EngineEvent, ProtocolEngine};
+    let secret = [0x42u8; 16];
+    let mut client = ProtocolEngine::client();
+    let mut server = ProtocolEngine::server();
+    assert_eq!(client.send(&StatusPingRequest { payload: 0 }).is_err(), true);
+    client.send(&HandshakePacket {
+        protocol_version: ProtocolVersion::V1_19_1,
+        server_address: "localhost",
+        server_port: 25565,
+        next_state: HandshakeNextState::Login,
+    }).unwrap();
+    assert_eq!(client.poll_event(), Some(EngineEvent::StateChanged(PacketState::Login)));
+    client.send(&LoginStart { name: "jenya705", signature_data: None }).unwrap();
+    client.enable_encryption(&secret).unwrap();
+    server.receive(&client.poll_transmit().unwrap());
+    assert_eq!(server.poll_frame().unwrap().unwrap().id, HandshakePacket::ID);
+    assert_eq!(server.state(), PacketState::Login);
+    assert_eq!(server.poll_frame().unwrap().unwrap().id, LoginStart::ID);
+    server.enable_encryption(&secret).unwrap();
+    server.send(&LoginSetCompression { threshold: 0 }).unwrap();
+    server.send(&LoginSuccess { uuid: Uuid::nil(), name: "jenya705", properties: Cow::Borrowed(&[]) }).unwrap();
+    assert_eq!(server.state(), PacketState::Play);
+    client.receive(&server.poll_transmit().unwrap());
+    assert_eq!(client.poll_frame().unwrap().unwrap().id, LoginSetCompression::ID);
+    assert_eq!(client.poll_event(), Some(EngineEvent::EncryptionEnabled));
+    assert_eq!(client.poll_event(), Some(EngineEvent::CompressionEnabled(crate::packet_compression::PacketCompression::new(0).unwrap())));
+    let frame = client.poll_frame().unwrap().unwrap();
+    assert_eq!(frame.read_packet::<LoginSuccess>().unwrap().name, "jenya705");
+    assert_eq!(client.state(), PacketState::Play);
+    assert_eq!(client.poll_transmit(), None);
+}
+
+#[cfg(feature = "packet_default")]
+#[test]
+fn packet_set_tests() {
+    use crate::packet_default::*;
+    let mut write = Vec::new();
+    ServerboundStatus::PingRequest(StatusPingRequest { payload: 7 }).write_packet(&mut write).unwrap();
+    assert_eq!(
+        ServerboundStatus::read_packet(PacketFrame::new(&write).unwrap()).unwrap(),
+        ServerboundStatus::PingRequest(StatusPingRequest { payload: 7 })
+    );
+    assert_eq!(ServerboundStatus::PingRequest(StatusPingRequest { payload: 7 }).id(), 0x01);
+    match ServerboundStatus::read_packet(PacketFrame { id: 0x05, body: &[0x01] }) {
+        Err(PacketSetError::UnknownId(unknown)) => {
+            assert_eq!(unknown.id, 0x05);
+            assert_eq!(unknown.state, PacketState::Status);
+            assert_eq!(unknown.body, &[0x01]);
+        }
+        _ => panic!("Unknown packet id error expected"),
+    }
+}
+
+#[cfg(feature = "packet_default")]
+#[test]
+fn registry_tests() {
+    use crate::packet_default::{StatusPingRequest, StatusPingResponse};
+    use crate::packet_registry::PacketRegistry;
+    let mut registry = PacketRegistry::new();
+    registry.register::<StatusPingRequest>().unwrap();
+    assert_eq!(registry.register::<StatusPingRequest>().is_err(), true);
+    assert_eq!(registry.key_of::<StatusPingRequest>(), Some((PacketState::Status, PacketBound::Server, 0x01)));
+    let mut write = Vec::new();
+    registry.encode(&StatusPingRequest { payload: 9 }, &mut write).unwrap();
+    assert_eq!(registry.encode(&StatusPingResponse { payload: 9 }, &mut write).is_err(), true);
+    let frame = PacketFrame::new(&write).unwrap();
+    let packet = registry.decode(PacketState::Status, PacketBound::Server, frame).unwrap().unwrap();
+    assert_eq!(packet.downcast::<StatusPingRequest>().unwrap().payload, 9);
+    assert_eq!(registry.decode(PacketState::Status, PacketBound::Client, frame).unwrap().is_none(), true);
+    assert_eq!(registry.unregister((PacketState::Status, PacketBound::Server, 0x01)), true);
+    assert_eq!(registry.key_of::<StatusPingRequest>(), None);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn versioned_tests() {
+    use crate::derive::*;
+
+    #[derive(PacketWritable, PacketReadable, Debug, PartialEq)]
+    struct VersionedInner {
+        #[since(761)]
+        flag: Option<bool>,
+    }
+
+    #[derive(Packet, PacketWritable, PacketReadable, Debug, PartialEq)]
+    #[packet(id = { 759 => 0x00, 763 => 0x01 }, bound = Client, state = Play)]
+    struct VersionedPacketTest {
+        #[variant(VarInt)]
+        value: i32,
+        #[until(761)]
+        old: Option<u8>,
+        #[since(761)]
+        #[variant(VarInt)]
+        new: Option<i32>,
+        #[versioned]
+        inner: VersionedInner,
+    }
+
+    assert_eq!(VersionedPacketTest::id_for(758), None);
+    assert_eq!(VersionedPacketTest::id_for(760), Some(0x00));
+    assert_eq!(VersionedPacketTest::id_for(763), Some(0x01));
+    assert_eq!(VersionedPacketTest::id(), 0x01);
+
+    let old = VersionedPacketTest { value: 1, old: Some(2), new: None, inner: VersionedInner { flag: None } };
+    let mut write = Vec::new();
+    old.write_versioned(&mut write, 760).unwrap();
+    assert_eq!(write, &[0x01, 0x02]);
+    assert_eq!(VersionedPacketTest::read_versioned(&mut SlicePacketRead::new(&write), 760).unwrap(), old);
+    assert_eq!(old.write_versioned(&mut Vec::new(), 761).is_err(), true);
+
+    let new = VersionedPacketTest { value: 1, old: None, new: Some(300), inner: VersionedInner { flag: Some(true) } };
+    let mut write = Vec::new();
+    new.write(&mut write).unwrap();
+    assert_eq!(write, &[0x01, 0xac, 0x02, 0x01]);
+    assert_eq!(VersionedPacketTest::read_versioned(&mut SlicePacketRead::new(&write), 763).unwrap(), new);
+
+    #[derive(PacketSet, Debug, PartialEq)]
+    #[packet_set(bound = Client, state = Play)]
+    enum VersionedPacketSet {
+        Test(VersionedPacketTest),
+    }
+
+    let data = packet_data_versioned(&old, 760).unwrap();
+    assert_eq!(data, &[0x00, 0x01, 0x02]);
+    let mut write = Vec::new();
+    VersionedPacketSet::Test(old).write_packet_versioned(&mut write, 760).unwrap();
+    assert_eq!(write, data);
+    let frame = PacketFrame::new(&data).unwrap();
+    assert_eq!(frame.read_packet_versioned::<VersionedPacketTest>(760).is_ok(), true);
+    assert_eq!(frame.read_packet_versioned::<VersionedPacketTest>(763).is_err(), true);
+    match VersionedPacketSet::read_packet_versioned(frame, 760).unwrap() {
+        VersionedPacketSet::Test(packet) => assert_eq!(packet.old, Some(2)),
+    }
+    assert_eq!(matches!(VersionedPacketSet::read_packet_versioned(frame, 763), Err(PacketSetError::UnknownId(_))), true);
+    let set = VersionedPacketSet::Test(new);
+    assert_eq!(set.id_for(758), None);
+    assert_eq!(set.write_packet_versioned(&mut Vec::new(), 758).is_err(), true);
+    let mut write = Vec::new();
+    set.write_packet_versioned(&mut write, 763).unwrap();
+    assert_eq!(write, &[0x01, 0x01, 0xac, 0x02, 0x01]);
+}
+
+#[test]
+fn protocol_version_tests() {
+    assert_eq!(ProtocolVersion::from_release("1.19.2"), Some(ProtocolVersion(760)));
+    assert_eq!(ProtocolVersion::from_release("1.19.5"), None);
+    assert_eq!(ProtocolVersion::V1_19_1.releases().collect::<Vec<_>>(), vec!["1.19.1", "1.19.2"]);
+    assert_eq!(ProtocolVersion::V1_19_1.name(), Some("1.19.2"));
+    assert_eq!(ProtocolVersion(760).to_string(), "1.19.2 (760)");
+    assert_eq!(ProtocolVersion(1).is_known(), false);
+    assert_eq!(ProtocolVersion::known().next(), Some(ProtocolVersion::OLDEST));
+    assert_eq!(ProtocolVersion::known().last(), Some(ProtocolVersion::LATEST));
+    assert_eq!(ProtocolVersion::known().filter(|version| *version == 760).count(), 1);
+    assert!(ProtocolVersion::V1_18_2 < ProtocolVersion::V1_19);
+    assert_eq!(ProtocolVersion::V1_18_2.has_chat_signing(), false);
+    assert_eq!(ProtocolVersion::V1_19.has_chat_signing(), true);
+    assert_eq!(ProtocolVersion::V1_20.has_configuration_state(), false);
+    assert_eq!(ProtocolVersion::V1_20_2.has_configuration_state(), true);
+    assert_eq!(ProtocolVersion::V1_20_2.has_network_nbt(), true);
+    assert_eq!(ProtocolVersion::V1_20_2.has_nbt_text_components(), false);
+    assert_eq!(ProtocolVersion::V1_20_3.has_nbt_text_components(), true);
+    let mut write = Vec::new();
+    ProtocolVersion::V1_19_1.write(&mut write).unwrap();
+    assert_eq!(write, &[0xf8, 0x05]);
+    assert_eq!(ProtocolVersion::read(&mut SlicePacketRead::new(&write)).unwrap(), ProtocolVersion::V1_19_1);
+}
+
+#[cfg(all(feature = "packet_default", feature = "compression", feature = "fastnbt"))]
+#[test]
+fn configuration_tests() {
+    use uuid::Uuid;
+    use crate::packet_default::*;
+    use bird_chat::identifier::Identifier;
+    use crate::packet_engine::ProtocolEngine;
+    // Finish acknowledged is 0x02 in 1.20.2 and 0x03 since 1.20.5
+    for (version, finish_id) in [(ProtocolVersion::V1_20_2, 0x02), (ProtocolVersion::V1_20_5, 0x03)] {
+        let mut client = ProtocolEngine::client();
+        let mut server = ProtocolEngine::server();
+        client.send(&HandshakePacket {
+            protocol_version: version,
+            server_address: "localhost",
+            server_port: 25565,
+            next_state: HandshakeNextState::Login,
+        }).unwrap();
+        server.receive(&client.poll_transmit().unwrap());
+        server.poll_frame().unwrap().unwrap();
+        assert_eq!(server.version(), version);
+        server.send(&LoginSuccess { uuid: Uuid::nil(), name: "jenya705", properties: Cow::Borrowed(&[]) }).unwrap();
+        assert_eq!(server.state(), PacketState::Login);
+        client.send(&LoginAcknowledged).unwrap();
+        assert_eq!(client.state(), PacketState::Configuration);
+        client.send(&ConfigurationFinishAcknowledged).unwrap();
+        assert_eq!(client.state(), PacketState::Play);
+        server.receive(&client.poll_transmit().unwrap());
+        server.poll_frame().unwrap().unwrap();
+        assert_eq!(server.state(), PacketState::Configuration);
+        let frame = server.poll_frame().unwrap().unwrap();
+        assert_eq!(frame.id, finish_id);
+        assert_eq!(
+            server.read_packet::<ServerboundConfiguration>(frame.as_frame()).unwrap(),
+            ServerboundConfiguration::FinishAcknowledged(ConfigurationFinishAcknowledged),
+        );
+        assert_eq!(server.state(), PacketState::Play);
+        // Commands packet is known only since 1.20.5
+        assert_eq!(server.send(&PlayCommands { graph: Default::default() }).is_ok(), version == ProtocolVersion::V1_20_5);
+    }
+
+    let mut nbt = std::collections::HashMap::new();
+    nbt.insert("height".to_string(), fastnbt::Value::Int(384));
+    let entries = [RegistryDataEntry {
+        id: Identifier::new_fulled("minecraft:overworld").unwrap(),
+        data: Some(fastnbt::Value::Compound(nbt)),
+    }];
+    let registry_data = ConfigurationRegistryData {
+        codec: None,
+        registry_id: Some(Identifier::new_fulled("minecraft:dimension_type").unwrap()),
+        entries: Some(Cow::Borrowed(&entries)),
+    };
+    let mut write = Vec::new();
+    ClientboundConfiguration::RegistryData(registry_data.clone()).write_packet(&mut write).unwrap();
+    assert_eq!(
+        ClientboundConfiguration::read_packet(PacketFrame::new(&write).unwrap()).unwrap(),
+        ClientboundConfiguration::RegistryData(registry_data),
+    );
+    let registry_data = ConfigurationRegistryData {
+        codec: entries[0].data.clone(),
+        registry_id: None,
+        entries: None,
+    };
+    let mut write = Vec::new();
+    registry_data.write_versioned(&mut write, 764).unwrap();
+    // Network nbt has no root name
+    assert_eq!(&write[..3], &[0x0a, 0x03, 0x00]);
+    assert_eq!(ConfigurationRegistryData::read_versioned(&mut SlicePacketRead::new(&write), 764).unwrap(), registry_data);
+    assert_eq!(ConfigurationRegistryData::id_for(764), Some(0x05));
+    assert_eq!(ConfigurationRegistryData::id_for(763), None);
+}
+
+#[cfg(all(feature = "packet_default", feature = "cookie"))]
+#[test]
+fn cookie_tests() {
+    use bird_chat::identifier::Identifier;
+    use crate::packet_cookie::*;
+    use crate::packet_default::*;
+    let signer = CookieSigner::new(b"lobby secret").unwrap();
+    let key = Identifier::new_fulled("lobby:session").unwrap();
+    let cookie = signer.sign(&key, b"jenya705").unwrap();
+    assert_eq!(cookie.len(), 8 + COOKIE_MAC_LENGTH);
+    let mut write = Vec::new();
+    PlayStoreCookie { key: key.clone(), payload: &cookie }.write(&mut write).unwrap();
+    let store = PlayStoreCookie::read(&mut SlicePacketRead::new(&write)).unwrap();
+    let response = LoginCookieResponse { key: store.key, payload: Some(store.payload) };
+    let mut write = Vec::new();
+    ServerboundLogin::CookieResponse(response).write_packet(&mut write).unwrap();
+    let response = match ServerboundLogin::read_packet(PacketFrame::new(&write).unwrap()).unwrap() {
+        ServerboundLogin::CookieResponse(response) => response,
+        _ => panic!("Cookie response expected"),
+    };
+    assert_eq!(signer.verify(&response.key, response.payload.unwrap()).unwrap(), b"jenya705");
+    assert_eq!(signer.verify(&Identifier::new_fulled("lobby:other").unwrap(), &cookie).is_err(), true);
+    let mut forged = cookie.clone();
+    forged[0] ^= 1;
+    assert_eq!(signer.verify(&key, &forged).is_err(), true);
+    assert_eq!(CookieSigner::new(b"other").unwrap().verify(&key, &cookie).is_err(), true);
+    assert_eq!(signer.sign(&key, &[0; MAX_COOKIE_LENGTH]).is_err(), true);
+    let mut write = Vec::new();
+    HandshakeNextState::Transfer.write(&mut write).unwrap();
+    assert_eq!(write, &[0x03]);
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn play_maintenance_tests() {
+    use bird_chat::component::{BaseComponent, Component, TextComponent};
+    use bird_chat::identifier::Identifier;
+    use crate::packet_default::*;
+    let mut writes = Vec::new();
+    let reason = Component::Text(TextComponent {
+        text: Cow::Borrowed("Server is restarting"),
+        base: BaseComponent {
+            bold: None,
+            italic: None,
+            underlined: None,
+            strikethrough: None,
+            obfuscated: None,
+            font: None,
+            color: None,
+            insertion: None,
+            extra: Cow::Owned(vec![Component::Text(TextComponent {
+                text: Cow::Borrowed(" soon"),
+                base: BaseComponent {
+                    bold: None,
+                    italic: None,
+                    underlined: None,
+                    strikethrough: None,
+                    obfuscated: None,
+                    font: None,
+                    color: None,
+                    insertion: None,
+                    extra: Cow::Borrowed(&[]),
+                    click_event: None,
+                    hover_event: None,
+                },
+            }), text_component(", "), text_component("wait")]),
+            click_event: None,
+            hover_event: None,
+        },
+    });
+    let packets = [
+        ClientboundPlay::BundleDelimiter(PlayBundleDelimiter),
+        ClientboundPlay::KeepAlive(PlayClientboundKeepAlive { id: 705 }),
+        ClientboundPlay::Disconnect(PlayDisconnect { reason }),
+        ClientboundPlay::PluginMessage(PlayClientboundPluginMessage {
+            channel: Identifier::new_fulled("minecraft:brand").unwrap(),
+            data: &[0x05, b'b', b'i', b'r', b'd', b'!'],
+        }),
+    ];
+    for packet in packets.iter() {
+        let mut write = Vec::new();
+        packet.write_packet(&mut write).unwrap();
+        writes.push(write);
+    }
+    for (packet, write) in packets.iter().zip(writes.iter()) {
+        assert_eq!(&ClientboundPlay::read_packet(PacketFrame::new(write).unwrap()).unwrap(), packet);
+    }
+    // Play packets are known only in 1.20.5 and 1.20.6
+    assert_eq!(PlayClientboundKeepAlive::id_for(766), Some(0x26));
+    assert_eq!(PlayClientboundKeepAlive::id_for(767), None);
+    assert_eq!(packets[1].id_for(ProtocolVersion::LATEST.protocol()), None);
+    let mut write = Vec::new();
+    ServerboundPlay::Pong(PlayPong { id: 7 }).write_packet(&mut write).unwrap();
+    assert_eq!(write, &[0x27, 0x00, 0x00, 0x00, 0x07]);
+    // Plain text component is a string tag
+    let mut write = Vec::new();
+    PlayDisconnect::read(&mut SlicePacketRead::new(&[0x08, 0x00, 0x02, b'h', b'i'])).unwrap()
+        .write(&mut write).unwrap();
+    assert_eq!(write[0], 0x0a);
+    // Plain text children are strings, mixed with other children they are compounds with the empty key
+    for content in [
+        fastnbt::nbt!({ "text": "a", "extra": ["b", "c"] }),
+        fastnbt::nbt!({ "text": "a", "extra": [{ "": "b" }, { "text": "c", "bold": 1i8 }] }),
+    ] {
+        let mut write = Vec::new();
+        ProtocolNetworkNbt::write_variant(&content, &mut write).unwrap();
+        write.push(0x01);
+        let mut read = SlicePacketRead::new(&write);
+        let chat = PlaySystemChat::read(&mut read).unwrap();
+        assert_eq!(chat.overlay, true);
+        assert_eq!(read.available(), 0);
+        match chat.content {
+            Component::Text(text) => assert_eq!(text.base.extra.iter().map(|child| match child {
+                Component::Text(child) => child.text.as_ref(),
+                _ => panic!("Text component expected"),
+            }).collect::<Vec<_>>(), vec!["b", "c"]),
+            _ => panic!("Text component expected"),
+        }
+    }
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn join_game_tests() {
+    use bird_chat::identifier::Identifier;
+    use crate::packet_default::*;
+    let overworld = Identifier::new_fulled("minecraft:overworld").unwrap();
+    let mut writes = Vec::new();
+    let dimension_names = [overworld.clone()];
+    let overworld_type = DimensionTypeElement {
+        piglin_safe: false,
+        has_raids: true,
+        monster_spawn_light_level: fastnbt::Value::Int(0),
+        monster_spawn_block_light_limit: 0,
+        natural: true,
+        ambient_light: 0.0,
+        fixed_time: None,
+        infiniburn: "#minecraft:infiniburn_overworld".to_string(),
+        respawn_anchor_works: false,
+        has_skylight: true,
+        bed_works: true,
+        effects: "minecraft:overworld".to_string(),
+        min_y: -64,
+        height: 384,
+        logical_height: 384,
+        coordinate_scale: 1.0,
+        ultrawarm: false,
+        has_ceiling: false,
+    };
+    let nether_type = DimensionTypeElement {
+        piglin_safe: true,
+        has_raids: false,
+        natural: false,
+        ambient_light: 0.1,
+        fixed_time: Some(18000),
+        infiniburn: "#minecraft:infiniburn_nether".to_string(),
+        respawn_anchor_works: true,
+        has_skylight: false,
+        bed_works: false,
+        effects: "minecraft:the_nether".to_string(),
+        min_y: 0,
+        height: 256,
+        logical_height: 128,
+        coordinate_scale: 8.0,
+        ultrawarm: true,
+        has_ceiling: true,
+        ..overworld_type.clone()
+    };
+    let biome = |name: &str, id, temperature, downfall| CodecRegistryEntry {
+        name: name.to_string(),
+        id,
+        element: BiomeElement {
+            precipitation: None,
+            has_precipitation: Some(downfall > 0.0),
+            temperature,
+            downfall,
+            temperature_modifier: None,
+            effects: fastnbt::nbt!({ "sky_color": 7907327, "fog_color": 12638463, "water_color": 4159204 }),
+        },
+    };
+    let codec = RegistryCodec {
+        dimension_type: CodecRegistry {
+            registry_type: "minecraft:dimension_type".to_string(),
+            value: vec![
+                CodecRegistryEntry { name: "minecraft:overworld".to_string(), id: 0, element: overworld_type },
+                CodecRegistryEntry { name: "minecraft:the_nether".to_string(), id: 1, element: nether_type },
+            ],
+        },
+        biome: CodecRegistry {
+            registry_type: "minecraft:worldgen/biome".to_string(),
+            value: vec![biome("minecraft:plains", 0, 0.8, 0.4), biome("minecraft:desert", 1, 2.0, 0.0)],
+        },
+        chat_type: CodecRegistry { registry_type: "minecraft:chat_type".to_string(), value: vec![] },
+        damage_type: None,
+    };
+    let legacy = PlayJoinGameLegacy {
+        entity_id: 7,
+        is_hardcore: false,
+        game_mode: GameMode::Creative,
+        previous_game_mode: PreviousGameMode::None,
+        dimension_names: Cow::Borrowed(&dimension_names),
+        registry_codec: codec,
+        dimension_type: overworld.clone(),
+        dimension_name: overworld.clone(),
+        hashed_seed: 705,
+        max_players: 20,
+        view_distance: 10,
+        simulation_distance: 8,
+        reduced_debug_info: false,
+        enable_respawn_screen: true,
+        is_debug: false,
+        is_flat: false,
+        death_location: Some((overworld.clone(), BlockPosition { x: 1, y: -2, z: 3 })),
+        portal_cooldown: None,
+    };
+    let mut write = Vec::new();
+    legacy.write_versioned(&mut write, 760).unwrap();
+    writes.push(write);
+    let read = PlayJoinGameLegacy::read_versioned(&mut SlicePacketRead::new(&writes[0]), 760).unwrap();
+    assert_eq!(read, legacy);
+    assert_eq!(read.registry_codec.dimension_type.value[0].element.height, 384);
+    assert_eq!(read.registry_codec.dimension_type.value[1].element.fixed_time, Some(18000));
+    assert_eq!(read.registry_codec.biome.value[1].name, "minecraft:desert");
+    assert_eq!(PlayJoinGameLegacy::id_for(763), Some(0x28));
+    assert_eq!(PlayJoinGameLegacy::id_for(764), None);
+
+    let join_game = PlayJoinGame {
+        entity_id: 7,
+        is_hardcore: false,
+        dimension_names: Cow::Borrowed(&dimension_names),
+        max_players: 20,
+        view_distance: 10,
+        simulation_distance: 8,
+        reduced_debug_info: false,
+        enable_respawn_screen: true,
+        do_limited_crafting: false,
+        dimension_type: DimensionTypeRef::Id(0),
+        dimension_name: overworld.clone(),
+        hashed_seed: 705,
+        game_mode: GameMode::Survival,
+        previous_game_mode: PreviousGameMode::Spectator,
+        is_debug: false,
+        is_flat: true,
+        death_location: None,
+        portal_cooldown: 0,
+        enforces_secure_chat: Some(true),
+    };
+    let mut write = Vec::new();
+    ClientboundPlay::JoinGame(join_game.clone()).write_packet(&mut write).unwrap();
+    writes.push(write);
+    assert_eq!(
+        ClientboundPlay::read_packet(PacketFrame::new(&writes[1]).unwrap()).unwrap(),
+        ClientboundPlay::JoinGame(join_game.clone()),
+    );
+    assert_eq!(join_game.write_versioned(&mut Vec::new(), 764).is_err(), true);
+    let respawn = PlayRespawn {
+        dimension_type: DimensionTypeRef::Name(overworld.clone()),
+        dimension_name: overworld,
+        hashed_seed: 705,
+        game_mode: GameMode::Adventure,
+        previous_game_mode: PreviousGameMode::None,
+        is_debug: false,
+        is_flat: false,
+        death_location: None,
+        portal_cooldown: 0,
+        data_kept: 0x01,
+    };
+    let mut write = Vec::new();
+    respawn.write_versioned(&mut write, 765).unwrap();
+    writes.push(write);
+    assert_eq!(PlayRespawn::read_versioned(&mut SlicePacketRead::new(&writes[2]), 765).unwrap(), respawn);
+}
+
+#[cfg(feature = "packet_default")]
+#[test]
+fn player_movement_tests() {
+    use crate::packet_default::*;
+    let mut write = Vec::new();
+    1.5f64.write(&mut write).unwrap();
+    assert_eq!(write, &[0x3F, 0xF8, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(f64::read(&mut SlicePacketRead::new(&write)).unwrap(), 1.5);
+
+    let position = EntityPosition { x: 10.5, y: 64.0, z: -3.25 };
+    let mut write = Vec::new();
+    ServerboundPlay::SetPlayerPositionAndRotation(PlaySetPlayerPositionAndRotation {
+        position,
+        yaw: 90.0,
+        pitch: -45.0,
+        on_ground: true,
+    }).write_packet(&mut write).unwrap();
+    assert_eq!(write.len(), 1 + 24 + 8 + 1);
+    assert_eq!(
+        ServerboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap(),
+        ServerboundPlay::SetPlayerPositionAndRotation(PlaySetPlayerPositionAndRotation {
+            position,
+            yaw: 90.0,
+            pitch: -45.0,
+            on_ground: true,
+        }),
+    );
+
+    let synchronize = PlaySynchronizePlayerPosition {
+        position: EntityPosition { x: 1.0, y: 70.0, z: -1.0 },
+        yaw: 180.0,
+        pitch: 10.0,
+        flags: RelativeFlags::X.with(RelativeFlags::Z).with(RelativeFlags::X_ROT),
+        teleport_id: 3,
+    };
+    let mut write = Vec::new();
+    ClientboundPlay::SynchronizePlayerPosition(synchronize.clone()).write_packet(&mut write).unwrap();
+    assert_eq!(
+        ClientboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap(),
+        ClientboundPlay::SynchronizePlayerPosition(synchronize.clone()),
+    );
+    assert_eq!(synchronize.flags.contains(RelativeFlags::Y), false);
+    assert_eq!(
+        synchronize.resolve(position, (0.0, 5.0)),
+        (EntityPosition { x: 11.5, y: 70.0, z: -4.25 }, (180.0, 15.0)),
+    );
+    let mut write = Vec::new();
+    PlayConfirmTeleportation { teleport_id: synchronize.teleport_id }.write(&mut write).unwrap();
+    assert_eq!(write, &[0x03]);
+}
+
+#[test]
+fn angle_tests() {
+    let mut write = Vec::new();
+    Angle::write_variant(&std::f32::consts::PI, &mut write).unwrap();
+    Angle::write_variant(&-std::f32::consts::FRAC_PI_2, &mut write).unwrap();
+    // Values are rounded to the nearest step
+    Angle::write_variant(&(std::f32::consts::FRAC_PI_2 + 0.01), &mut write).unwrap();
+    assert_eq!(write, &[128, 192, 64]);
+    assert_eq!(<Angle as PacketVariantReadable<f32>>::read_variant(&mut SlicePacketRead::new(&[64])).unwrap(), std::f32::consts::FRAC_PI_2);
+    for step in 0..=255u8 {
+        let angle = <Angle as PacketVariantReadable<f32>>::read_variant(&mut SlicePacketRead::new(&[step])).unwrap();
+        let mut write = Vec::new();
+        Angle::write_variant(&angle, &mut write).unwrap();
+        assert_eq!(write, &[step]);
+    }
+    #[cfg(feature = "euclid")]
+    {
+        let mut write = Vec::new();
+        euclid::Angle::<f32>::degrees(90.0).write(&mut write).unwrap();
+        assert_eq!(write, &[64]);
+        let angle = euclid::Angle::<f64>::read(&mut SlicePacketRead::new(&write)).unwrap();
+        assert_eq!((angle.to_degrees() - 90.0).abs() < 1e-4, true);
+    }
+}
+
+#[cfg(feature = "packet_default")]
+#[test]
+fn entity_movement_tests() {
+    use crate::packet_default::*;
+    let from = EntityPosition { x: 0.0, y: 64.0, z: 0.0 };
+    let to = EntityPosition { x: 1.5, y: 63.75, z: -7.0 };
+    let delta = EntityDelta::between(from, to).unwrap();
+    assert_eq!(delta, EntityDelta { x: 6144, y: -1024, z: -28672 });
+    assert_eq!(delta.apply(from), to);
+    assert_eq!(EntityDelta::between(from, EntityPosition { x: 8.0, ..from }), None);
+    let velocity = EntityVelocity::from_blocks_per_tick(0.5, -10.0, 0.0);
+    assert_eq!(velocity, EntityVelocity { x: 4000, y: -31200, z: 0 });
+    assert_eq!(velocity.to_blocks_per_tick().0, 0.5);
+
+    let spawn = PlaySpawnEntity {
+        entity_id: 12,
+        entity_uuid: uuid::Uuid::from_u128(0x1234),
+        entity_type: 57,
+        position: to,
+        pitch: 0.0,
+        yaw: std::f32::consts::PI,
+        head_yaw: std::f32::consts::FRAC_PI_2,
+        data: 0,
+        velocity,
+    };
+    let entity_ids = [12, 300];
+    let mut writes = Vec::new();
+    for packet in [
+        ClientboundPlay::SpawnEntity(spawn),
+        ClientboundPlay::UpdateEntityPositionAndRotation(PlayUpdateEntityPositionAndRotation {
+            entity_id: 12,
+            delta,
+            yaw: std::f32::consts::PI,
+            pitch: 0.0,
+            on_ground: false,
+        }),
+        ClientboundPlay::SetEntityVelocity(PlaySetEntityVelocity { entity_id: 12, velocity }),
+        ClientboundPlay::RemoveEntities(PlayRemoveEntities { entity_ids: Cow::Borrowed(&entity_ids) }),
+    ] {
+        let mut write = Vec::new();
+        packet.write_packet(&mut write).unwrap();
+        writes.push((packet, write));
+    }
+    for (packet, write) in &writes {
+        assert_eq!(&ClientboundPlay::read_packet(PacketFrame::new(write).unwrap()).unwrap(), packet);
+    }
+    assert_eq!(writes[3].1, &[0x42, 0x02, 0x0C, 0xAC, 0x02]);
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn entity_metadata_tests() {
+    use bird_chat::identifier::Identifier;
+    use crate::packet_default::*;
+    use crate::packet_metadata::*;
+    let mut metadata = EntityMetadata::new();
+    metadata.set(0, EntityMetadataValue::Byte(0x02));
+    metadata.set(2, EntityMetadataValue::OptionalComponent(None));
+    metadata.set(6, EntityMetadataValue::Pose(Pose::Sneaking));
+    metadata.set(7, EntityMetadataValue::OptionalVarInt(Some(0)));
+    metadata.set(8, EntityMetadataValue::OptionalUuid(Some(uuid::Uuid::from_u128(5))));
+    metadata.set(9, EntityMetadataValue::Particles(vec![
+        Particle { particle_type: 0, data: ParticleData::None },
+        Particle { particle_type: 13, data: ParticleData::Dust { color: (1.0, 0.0, 0.5), scale: 2.0 } },
+        Particle {
+            particle_type: 45,
+            data: ParticleData::Vibration {
+                source: VibrationSource::Entity { entity_id: 3, eye_height: 1.5 },
+                ticks: 20,
+            },
+        },
+    ]));
+    metadata.set(10, EntityMetadataValue::OptionalGlobalPosition(Some((
+        Identifier::new_fulled("minecraft:overworld").unwrap(), BlockPosition { x: 1, y: 2, z: 3 },
+    ))));
+    metadata.set(11, EntityMetadataValue::VillagerData(VillagerData { villager_type: 2, profession: 5, level: 1 }));
+    metadata.set(12, EntityMetadataValue::Quaternion(0.0, 0.0, 0.0, 1.0));
+    metadata.set(0, EntityMetadataValue::Byte(0x20));
+    assert_eq!(metadata.get(0), Some(&EntityMetadataValue::Byte(0x20)));
+    assert_eq!(metadata.entries.len(), 9);
+
+    let packet = ClientboundPlay::SetEntityMetadata(PlaySetEntityMetadata { entity_id: 4, metadata });
+    let mut write = Vec::new();
+    packet.write_packet(&mut write).unwrap();
+    assert_eq!(write.last(), Some(&ENTITY_METADATA_END));
+    assert_eq!(ClientboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap(), packet);
+    let mut write = Vec::new();
+    EntityMetadataValue::OptionalVarInt(Some(0)).write(&mut write).unwrap();
+    assert_eq!(write, &[20, 1]);
+
+    let error = EntityMetadata::read(&mut SlicePacketRead::new(&[0x00, 0x40, 0x00, 0xFF])).unwrap_err();
+    assert_eq!(error.to_string(), "Unknown entity metadata serializer id 64");
+    assert_eq!(EntityMetadata::read(&mut SlicePacketRead::new(&[0xFF])).unwrap(), EntityMetadata::new());
+
+    for particle_type in [0, 3, 12, 15, 19, 21, 27, 29, 34, 36, 43, 46, 98, 100, 104, 106, 108] {
+        let particle = Particle { particle_type, data: ParticleData::None };
+        let mut write = Vec::new();
+        particle.write(&mut write).unwrap();
+        assert_eq!(Particle::read(&mut SlicePacketRead::new(&write)).unwrap(), particle);
+    }
+    for particle_type in [-1, 109, 0x7F] {
+        let mut write = Vec::new();
+        VarInt::write_variant(&particle_type, &mut write).unwrap();
+        let error = Particle::read(&mut SlicePacketRead::new(&write)).unwrap_err();
+        assert_eq!(error.to_string(), format!("Unknown particle type {}", particle_type));
+    }
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn chunk_tests() {
+    use crate::packet_chunk::*;
+    use crate::packet_default::*;
+    let air = PalettedContainer::from_values(PalettedContainerKind::BlockStates, &[0; 4096], 15).unwrap();
+    assert_eq!(air, PalettedContainer::single(PalettedContainerKind::BlockStates, 0));
+
+    // Two values need one bit, but block states indirect palette is at least 4 bits
+    let mut values = vec![0; 4096];
+    values[17] = 9;
+    let stone = PalettedContainer::from_values(PalettedContainerKind::BlockStates, &values, 15).unwrap();
+    assert_eq!(stone.bits_per_entry, 4);
+    assert_eq!(stone.palette, Palette::Indirect(vec![0, 9]));
+    assert_eq!(stone.data.len(), 256);
+    assert_eq!(stone.data[1], 1 << 4);
+    assert_eq!(stone.get(17), Some(9));
+    assert_eq!(stone.values().unwrap(), values);
+
+    // 300 values do not fit into indirect palette, 15 bits entries are not spanning longs
+    let values: Vec<i32> = (0..4096).map(|index| index % 300 * 100).collect();
+    let direct = PalettedContainer::from_values(PalettedContainerKind::BlockStates, &values, 15).unwrap();
+    assert_eq!(direct.palette, Palette::Direct);
+    assert_eq!(direct.data.len(), 1024);
+    assert_eq!(direct.values().unwrap(), values);
+    assert_eq!(PalettedContainer::from_values(PalettedContainerKind::BlockStates, &values, 8).is_err(), true);
+
+    let biome_values: Vec<i32> = (0..64).map(|index| index % 5).collect();
+    let biomes = PalettedContainer::from_values(PalettedContainerKind::Biomes, &biome_values, 6).unwrap();
+    assert_eq!(biomes.bits_per_entry, 3);
+    assert_eq!(biomes.data.len(), 4);
+    let many_biomes: Vec<i32> = (0..64).map(|index| index % 9).collect();
+    let many_biomes = PalettedContainer::from_values(PalettedContainerKind::Biomes, &many_biomes, 6).unwrap();
+    assert_eq!(many_biomes.palette, Palette::Direct);
+
+    let sections = vec![
+        ChunkSection { block_count: 1, block_states: stone, biomes },
+        ChunkSection { block_count: 4096, block_states: direct, biomes: many_biomes },
+        ChunkSection {
+            block_count: 0,
+            block_states: air,
+            biomes: PalettedContainer::single(PalettedContainerKind::Biomes, 1),
+        },
+    ];
+    let data = ChunkSection::write_all(&sections).unwrap();
+    assert_eq!(ChunkSection::read_all(&data).unwrap(), sections);
+    assert_eq!(&data[data.len() - 6..], &[0, 0, 0, 0, 1, 0]);
+
+    // Block states with 2 bits per entry are read with 4 bits, the way client reads them
+    let mut write = vec![0, 0, 2, 1, 0];
+    VarInt::write_variant(&256, &mut write).unwrap();
+    write.extend_from_slice(&[0; 256 * 8]);
+    write.extend_from_slice(&[0, 0, 0]);
+    let section = ChunkSection::read(&mut SlicePacketRead::new(&write)).unwrap();
+    assert_eq!(section.block_states.bits_per_entry, 4);
+
+    // Single value, bounds of the indirect palette and the direct palette, data is zeroed
+    let containers = [
+        (PalettedContainerKind::BlockStates, 0, &[0][..], 0),
+        (PalettedContainerKind::BlockStates, 4, &[1, 0], 256),
+        (PalettedContainerKind::BlockStates, 8, &[1, 0], 512),
+        (PalettedContainerKind::BlockStates, 9, &[], 586),
+        (PalettedContainerKind::Biomes, 0, &[0], 0),
+        (PalettedContainerKind::Biomes, 1, &[1, 0], 1),
+        (PalettedContainerKind::Biomes, 3, &[1, 0], 4),
+        (PalettedContainerKind::Biomes, 4, &[], 4),
+        (PalettedContainerKind::Biomes, 32, &[], 32),
+    ];
+    for (kind, bits, palette, length) in containers {
+        let mut write = vec![bits];
+        write.extend_from_slice(palette);
+        VarInt::write_variant(&length, &mut write).unwrap();
+        write.resize(write.len() + length as usize * 8, 0);
+        let mut read = SlicePacketRead::new(&write);
+        let container = match kind {
+            PalettedContainerKind::BlockStates => BlockStatesContainer::read_variant(&mut read).unwrap(),
+            PalettedContainerKind::Biomes => BiomesContainer::read_variant(&mut read).unwrap(),
+        };
+        assert_eq!(container.bits_per_entry, bits);
+        let mut written = Vec::new();
+        match kind {
+            PalettedContainerKind::BlockStates => BlockStatesContainer::write_variant(&container, &mut written).unwrap(),
+            PalettedContainerKind::Biomes => BiomesContainer::write_variant(&container, &mut written).unwrap(),
+        }
+        assert_eq!(written, write);
+    }
+    for bits in [33, 255] {
+        let error = BiomesContainer::read_variant(&mut SlicePacketRead::new(&[bits, 0, 0])).unwrap_err();
+        assert_eq!(error.to_string(), format!("Too many bits per entry: {}", bits));
+        let biomes = PalettedContainer {
+            kind: PalettedContainerKind::Biomes,
+            bits_per_entry: bits,
+            palette: Palette::Direct,
+            data: Vec::new(),
+        };
+        assert_eq!(BiomesContainer::write_variant(&biomes, &mut Vec::new()).is_err(), true);
+        let values: Vec<i32> = (0..64).collect();
+        let error = PalettedContainer::from_values(PalettedContainerKind::Biomes, &values, bits).unwrap_err();
+        assert_eq!(error.to_string(), format!("Too many bits per entry: {}", bits));
+    }
+    let values: Vec<i32> = (0..64).map(|index| index - 32).collect();
+    let direct = PalettedContainer::from_values(PalettedContainerKind::Biomes, &values, 32).unwrap();
+    assert_eq!(direct.values().unwrap(), values);
+
+    let sky_light = [0xFFu8; 2048];
+    let sky_lights = [&sky_light[..]];
+    let chunk = PlayChunkDataAndUpdateLight {
+        chunk_x: -1,
+        chunk_z: 2,
+        heightmaps: Heightmaps {
+            motion_blocking: Some(fastnbt::LongArray::new(vec![0; 37])),
+            world_surface: None,
+        },
+        data: &data,
+        block_entities: Cow::Owned(vec![
+            ChunkBlockEntity { packed_xz: 0x3A, y: 64, block_entity_type: 7, data: None },
+        ]),
+        light: LightData {
+            sky_light_mask: Cow::Owned(vec![0b10]),
+            block_light_mask: Cow::Owned(vec![0]),
+            empty_sky_light_mask: Cow::Owned(vec![0b01]),
+            empty_block_light_mask: Cow::Owned(vec![]),
+            sky_light: Cow::Borrowed(&sky_lights),
+            block_light: Cow::Owned(vec![]),
+        },
+    };
+    let mut write = Vec::new();
+    ClientboundPlay::ChunkDataAndUpdateLight(chunk.clone()).write_packet(&mut write).unwrap();
+    let read = ClientboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap();
+    assert_eq!(read, ClientboundPlay::ChunkDataAndUpdateLight(chunk.clone()));
+    assert_eq!(chunk.block_entities[0].x(), 3);
+    assert_eq!(chunk.block_entities[0].z(), 10);
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn slot_tests() {
+    use bird_chat::identifier::Identifier;
+    use crate::packet_slot::*;
+    use uuid::Uuid;
+    let mut item = ItemStack::new(802, 1);
+    item.set_component(ItemComponent::Damage(12));
+    item.set_component(ItemComponent::CustomName(text_component("Excalibur")));
+    item.set_component(ItemComponent::Enchantments { enchantments: vec![(13, 5)], show_in_tooltip: true });
+    item.set_component(ItemComponent::Damage(20));
+    item.removed_components.push(ItemComponent::CUSTOM_DATA);
+    assert_eq!(item.damage(), Some(20));
+    assert_eq!(item.components.len(), 3);
+    let slot = Slot(Some(item));
+    let mut write = Vec::new();
+    slot.write(&mut write).unwrap();
+    assert_eq!(&write[..6], &[0x01, 0xA2, 0x06, 0x03, 0x01, 0x03]);
+    let read = Slot::read(&mut SlicePacketRead::new(&write)).unwrap();
+    assert_eq!(read, slot);
+    assert_eq!(read.item().unwrap().custom_name().is_some(), true);
+    assert_eq!(slot.write_versioned(&mut Vec::new(), 765).is_err(), true);
+
+    let mut write = Vec::new();
+    Slot::empty().write(&mut write).unwrap();
+    Slot::empty().write_versioned(&mut write, 765).unwrap();
+    assert_eq!(write, &[0, 0]);
+
+    let mut writes = Vec::new();
+    let mut legacy = ItemStack::new(1, 64);
+    legacy.nbt = Some(fastnbt::nbt!({ "Damage": 3 }));
+    let legacy = Slot(Some(legacy));
+    for version in [760, 765] {
+        let mut write = Vec::new();
+        legacy.write_versioned(&mut write, version).unwrap();
+        writes.push((version, write));
+    }
+    for (version, write) in &writes {
+        assert_eq!(Slot::read_versioned(&mut SlicePacketRead::new(write), *version).unwrap(), legacy);
+    }
+    let mut write = Vec::new();
+    Slot::new(1, 3).write_versioned(&mut write, 760).unwrap();
+    assert_eq!(write, &[1, 1, 3, 0]);
+
+    let mut write = Vec::new();
+    let potion = PotionContents { potion_id: Some(5), ..Default::default() };
+    ItemComponent::PotionContents(potion).write(&mut write).unwrap();
+    assert_eq!(write, &[31, 1, 5, 0, 0]);
+    let mut write = Vec::new();
+    IdSet::Ids(vec![1, 2]).write(&mut write).unwrap();
+    Holder::<BannerPattern>::Registry(4).write(&mut write).unwrap();
+    assert_eq!(write, &[3, 1, 2, 5]);
+    let mut write = Vec::new();
+    ItemComponent::Lock(Cow::Borrowed("key")).write(&mut write).unwrap();
+    assert_eq!(write, &[54, 8, 0, 3, b'k', b'e', b'y']);
+
+    let mut shulker_box = ItemStack::new(522, 1);
+    shulker_box.set_component(ItemComponent::Container(vec![Slot::new(1, 64), Slot::empty(), Slot::new(802, 1)]));
+    shulker_box.set_component(ItemComponent::Lock(Cow::Borrowed("key")));
+    shulker_box.set_component(ItemComponent::BlockEntityData(fastnbt::nbt!({ "id": "minecraft:shulker_box" })));
+    let mut potion = ItemStack::new(1107, 1);
+    potion.set_component(ItemComponent::PotionContents(PotionContents {
+        potion_id: None,
+        custom_color: Some(0xFF0000),
+        custom_effects: vec![PotionEffect {
+            effect_id: 1,
+            details: PotionEffectDetails {
+                amplifier: 1,
+                duration: 200,
+                ambient: false,
+                show_particles: true,
+                show_icon: true,
+                hidden_effect: Some(Box::new(PotionEffectDetails {
+                    amplifier: 0,
+                    duration: 600,
+                    ambient: false,
+                    show_particles: true,
+                    show_icon: true,
+                    hidden_effect: None,
+                })),
+            },
+        }],
+    }));
+    let mut head = ItemStack::new(1068, 1);
+    head.set_component(ItemComponent::Profile(GameProfile {
+        name: Some(Cow::Borrowed("Notch")),
+        uuid: Some(Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5)),
+        properties: vec![ProfileProperty {
+            name: Cow::Borrowed("textures"),
+            value: Cow::Borrowed("e30="),
+            signature: None,
+        }],
+    }));
+    let mut book = ItemStack::new(1048, 1);
+    book.set_component(ItemComponent::WrittenBookContent(WrittenBookContent {
+        title: FilterableText { raw: Cow::Borrowed("Title"), filtered: None },
+        author: Cow::Borrowed("Notch"),
+        generation: 0,
+        pages: vec![FilterableComponent { raw: text_component("Page"), filtered: Some(text_component("****")) }],
+        resolved: true,
+    }));
+    book.set_component(ItemComponent::WritableBookContent {
+        pages: vec![FilterableText { raw: Cow::Borrowed("Draft"), filtered: None }],
+    });
+    let mut armor = ItemStack::new(870, 1);
+    armor.set_component(ItemComponent::Trim(Box::new(ArmorTrim {
+        material: Holder::Inline(TrimMaterial {
+            asset_name: Cow::Borrowed("amethyst"),
+            ingredient: 795,
+            item_model_index: 1.0,
+            override_armor_materials: vec![ArmorMaterialOverride {
+                armor_material: 1,
+                asset_name: Cow::Borrowed("amethyst_darker"),
+            }],
+            description: text_component("Amethyst"),
+        }),
+        pattern: Holder::Registry(3),
+        show_in_tooltip: true,
+    })));
+    armor.set_component(ItemComponent::AttributeModifiers {
+        modifiers: vec![AttributeModifier {
+            attribute_id: 0,
+            uuid: Uuid::from_u128(1),
+            name: Cow::Borrowed("armor"),
+            amount: 2.0,
+            operation: AttributeOperation::AddValue,
+            slot: 7,
+        }],
+        show_in_tooltip: false,
+    });
+    let mut tool = ItemStack::new(830, 1);
+    tool.set_component(ItemComponent::Food(Food {
+        nutrition: 4,
+        saturation: 2.4,
+        can_always_eat: false,
+        eat_seconds: 1.6,
+        effects: vec![FoodEffect {
+            effect: PotionEffect { effect_id: 17, details: PotionEffectDetails {
+                amplifier: 0,
+                duration: 600,
+                ambient: false,
+                show_particles: true,
+                show_icon: true,
+                hidden_effect: None,
+            } },
+            probability: 0.8,
+        }],
+    }));
+    tool.set_component(ItemComponent::Tool(Tool {
+        rules: vec![
+            ToolRule {
+                blocks: IdSet::Tag(Identifier::new_fulled("minecraft:mineable/pickaxe").unwrap()),
+                speed: Some(8.0),
+                correct_for_drops: Some(true),
+            },
+            ToolRule { blocks: IdSet::Ids(vec![1, 2]), speed: None, correct_for_drops: Some(false) },
+        ],
+        default_mining_speed: 1.0,
+        damage_per_block: 1,
+    }));
+    tool.set_component(ItemComponent::CanBreak {
+        predicates: vec![BlockPredicate {
+            blocks: Some(IdSet::Ids(vec![1])),
+            properties: Some(vec![
+                PropertyMatcher { name: Cow::Borrowed("axis"), value: PropertyValue::Exact(Cow::Borrowed("y")) },
+                PropertyMatcher {
+                    name: Cow::Borrowed("age"),
+                    value: PropertyValue::Range { min: Some(Cow::Borrowed("2")), max: None },
+                },
+            ]),
+            nbt: None,
+        }],
+        show_in_tooltip: true,
+    });
+    tool.set_component(ItemComponent::IntangibleProjectile);
+    tool.set_component(ItemComponent::Recipes(vec![Cow::Borrowed("minecraft:stick")]));
+    tool.set_component(ItemComponent::Fireworks {
+        flight_duration: 2,
+        explosions: vec![FireworkExplosion {
+            shape: FireworkShape::Star,
+            colors: vec![0xFF0000],
+            fade_colors: Vec::new(),
+            has_trail: true,
+            has_twinkle: false,
+        }],
+    });
+    let slots = [shulker_box, potion, head, book, armor, tool].map(|item| Slot(Some(item)));
+    let mut writes = Vec::new();
+    for slot in &slots {
+        let mut write = Vec::new();
+        slot.write(&mut write).unwrap();
+        writes.push(write);
+    }
+    for (slot, write) in slots.iter().zip(&writes) {
+        let mut read = SlicePacketRead::new(write);
+        assert_eq!(&Slot::read(&mut read).unwrap(), slot);
+        assert_eq!(read.available(), 0);
+    }
+    assert_eq!(slots[0].item().unwrap().container().map(|slots| slots.len()), Some(3));
+    let mut write = Vec::new();
+    ItemComponent::Recipes(Vec::new()).write(&mut write).unwrap();
+    assert_eq!(write, &[42, 9, 8, 0, 0, 0, 0]);
+    assert_eq!(ItemComponent::read(&mut SlicePacketRead::new(&[42, 9, 0, 0, 0, 0, 0])).unwrap(),
+               ItemComponent::Recipes(Vec::new()));
+
+    // Components of later versions are not modelled, so reading stops at the first of them
+    let mut write = Vec::new();
+    ItemComponent::Raw { id: 56, data: Cow::Borrowed(&[0x02, 0x00]) }.write(&mut write).unwrap();
+    assert_eq!(write, &[56, 0x02, 0x00]);
+    let error = Slot::read(&mut SlicePacketRead::new(&[1, 1, 2, 0, 3, 5, 56, 2, 0])).unwrap_err();
+    assert_eq!(error.to_string(), "Item component 56 is not supported and can not be skipped");
+
+    let nested = nested_slot(MAX_NESTING_DEPTH);
+    assert_eq!(Slot::read(&mut SlicePacketRead::new(&nested)).is_ok(), true);
+    for depth in [MAX_NESTING_DEPTH + 1, 100_000] {
+        let error = Slot::read(&mut SlicePacketRead::new(&nested_slot(depth))).unwrap_err();
+        assert_eq!(error.to_string(), "Nesting is deeper than 32");
+    }
+    // Potion effect with 100000 hidden effects
+    let mut effect = vec![1];
+    for _ in 0..100_000 {
+        effect.extend_from_slice(&[0, 1, 0, 1, 1, 1]);
+    }
+    effect.extend_from_slice(&[0, 1, 0, 1, 1, 0]);
+    assert_eq!(PotionEffect::read(&mut SlicePacketRead::new(&effect)).is_err(), true);
+}
+
+/// Slot with the shulker boxes which are nested into each other by the container component.
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+fn nested_slot(depth: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(depth * 7 + 1);
+    for _ in 0..depth {
+        bytes.extend_from_slice(&[1, 0x8A, 0x04, 1, 0, 51, 1]);
+    }
+    bytes.push(0);
+    bytes
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+fn text_component(text: &str) -> bird_chat::component::Component<'_> {
+    use bird_chat::component::{BaseComponent, Component, TextComponent};
+    Component::Text(TextComponent {
+        text: Cow::Borrowed(text),
+        base: BaseComponent {
+            bold: None,
+            italic: None,
+            underlined: None,
+            strikethrough: None,
+            obfuscated: None,
+            font: None,
+            color: None,
+            insertion: None,
+            extra: Cow::Borrowed(&[]),
+            click_event: None,
+            hover_event: None,
+        },
+    })
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn inventory_tests() {
+    use crate::packet_default::*;
+    use crate::packet_slot::*;
+    let slots = [Slot::new(1, 64), Slot::empty(), Slot::new(802, 1)];
+    let changed_slots = [(36, Slot::new(1, 32)), (37, Slot::empty())];
+    let mut writes = Vec::new();
+    for packet in [
+        ClientboundPlay::OpenScreen(PlayOpenScreen {
+            window_id: 3,
+            window_type: ScreenType::Generic9x3,
+            title: text_component("Chest"),
+        }),
+        ClientboundPlay::SetContainerContent(PlaySetContainerContent {
+            window_id: 3,
+            state_id: 7,
+            slots: Cow::Borrowed(&slots),
+            carried_item: Slot::empty(),
+        }),
+        ClientboundPlay::SetContainerSlot(PlaySetContainerSlot {
+            window_id: -1,
+            state_id: 8,
+            slot: -1,
+            slot_data: Slot::new(1, 32),
+        }),
+        ClientboundPlay::ContainerProperty(PlayContainerProperty { window_id: 3, property: 0, value: 200 }),
+    ] {
+        let mut write = Vec::new();
+        packet.write_packet(&mut write).unwrap();
+        writes.push((packet, write));
+    }
+    for (packet, write) in &writes {
+        assert_eq!(&ClientboundPlay::read_packet(PacketFrame::new(write).unwrap()).unwrap(), packet);
+    }
+    assert_eq!(writes[1].1, &[0x13, 3, 7, 3, 64, 1, 0, 0, 0, 1, 0xA2, 0x06, 0, 0, 0]);
+
+    let click = ServerboundPlay::ClickContainer(PlayClickContainer {
+        window_id: 0,
+        state_id: 8,
+        slot: 36,
+        button: 1,
+        mode: ClickMode::Pickup,
+        changed_slots: Cow::Borrowed(&changed_slots),
+        carried_item: Slot::new(1, 32),
+    });
+    let mut write = Vec::new();
+    click.write_packet(&mut write).unwrap();
+    let read = ServerboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap();
+    assert_eq!(read, click);
+    match read {
+        ServerboundPlay::ClickContainer(click) => assert_eq!(click.changed_slots[0].1.item().unwrap().count, 32),
+        _ => unreachable!(),
+    }
+    let mut write = Vec::new();
+    ServerboundPlay::ClickContainer(PlayClickContainer {
+        window_id: 0,
+        state_id: 8,
+        slot: 40,
+        button: 2,
+        mode: ClickMode::Swap,
+        changed_slots: Cow::Borrowed(&[]),
+        carried_item: Slot::empty(),
+    }).write_packet(&mut write).unwrap();
+    assert_eq!(write, &[0x0E, 0, 8, 0, 40, 2, 2, 0, 0]);
+
+    let too_many: Vec<(i16, Slot)> = (0..=MAX_CHANGED_SLOTS as i16).map(|slot| (slot, Slot::empty())).collect();
+    let mut click = PlayClickContainer {
+        window_id: 0,
+        state_id: 8,
+        slot: 40,
+        button: 2,
+        mode: ClickMode::Swap,
+        changed_slots: Cow::Borrowed(&too_many),
+        carried_item: Slot::empty(),
+    };
+    assert_eq!(click.write(&mut Vec::new()).is_err(), true);
+    click.changed_slots = Cow::Borrowed(&too_many[..MAX_CHANGED_SLOTS]);
+    let mut write = Vec::new();
+    click.write(&mut write).unwrap();
+    assert_eq!(PlayClickContainer::read(&mut SlicePacketRead::new(&write)).unwrap(), click);
+    // Changed slot count is 129
+    let error = PlayClickContainer::read(&mut SlicePacketRead::new(&[0, 8, 0, 40, 2, 2, 0x81, 0x01])).unwrap_err();
+    assert_eq!(error.to_string(), "Too many changed slots: 129");
+    // Untrusted length is not allocated before the elements are read
+    let content = [1, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0x07];
+    assert_eq!(PlaySetContainerContent::read(&mut SlicePacketRead::new(&content)).is_err(), true);
+
+    // Slots of the client are nested no deeper than the limit
+    let mut creative = vec![0x32, 0, 36];
+    creative.extend_from_slice(&nested_slot(100_000));
+    let error = ServerboundPlay::read_packet(PacketFrame::new(&creative).unwrap()).unwrap_err();
+    assert_eq!(error.to_string(), "Nesting is deeper than 32");
+    let mut click = vec![0x0E, 0, 8, 0, 40, 2, 2, 1, 0, 36];
+    click.extend_from_slice(&nested_slot(MAX_NESTING_DEPTH + 1));
+    click.push(0);
+    let error = ServerboundPlay::read_packet(PacketFrame::new(&click).unwrap()).unwrap_err();
+    assert_eq!(error.to_string(), "Nesting is deeper than 32");
+    let mut click = vec![0x0E, 0, 8, 0, 40, 2, 2, 0];
+    click.extend_from_slice(&nested_slot(MAX_NESTING_DEPTH + 1));
+    assert_eq!(ServerboundPlay::read_packet(PacketFrame::new(&click).unwrap()).is_err(), true);
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn chat_tests() {
+    use crate::packet_chat::*;
+    use crate::packet_default::*;
+    let signatures: Vec<MessageSignature> = (0..25u8).map(|index| [index; MESSAGE_SIGNATURE_LENGTH]).collect();
+    let mut tracker = LastSeenMessagesTracker::new();
+    let mut validator = LastSeenMessagesValidator::new();
+    for signature in &signatures[..3] {
+        validator.add_pending(signature);
+        assert_eq!(tracker.add_pending(signature, true), true);
+    }
+    assert_eq!(tracker.add_pending(&signatures[2], true), false);
+    tracker.ignore_pending(&signatures[1]);
+    let (seen, update) = tracker.generate_update();
+    assert_eq!(seen, vec![signatures[0], signatures[2]]);
+    // Three messages are at the end of the window of 20 messages
+    assert_eq!(update, LastSeenUpdate { offset: 3, acknowledged: [0, 0, 0b1010] });
+    assert_eq!(validator.apply_update(&update).unwrap(), seen);
+
+    for signature in &signatures[3..25] {
+        validator.add_pending(signature);
+        tracker.add_pending(signature, true);
+    }
+    let (seen, update) = tracker.generate_update();
+    assert_eq!(seen.len(), LAST_SEEN_MESSAGES);
+    assert_eq!(seen[0], signatures[5]);
+    assert_eq!(update.offset, 22);
+    assert_eq!(validator.apply_update(&update).unwrap(), seen);
+    let error = validator.apply_update(&LastSeenUpdate { offset: 1, acknowledged: [0xFF, 0xFF, 0x0F] }).unwrap_err();
+    assert_eq!(error.to_string(), "Advanced last seen window by 1 messages, but expected at most 0");
+    let error = validator.apply_update(&LastSeenUpdate { offset: 0, acknowledged: [0xFF, 0xFF, 0x0E] }).unwrap_err();
+    assert_eq!(error.to_string(), "Last seen update ignored previously acknowledged message at index 16");
+
+    let mut writes = Vec::new();
+    let previous_messages = [PackedMessageSignature::Id(4), PackedMessageSignature::Signature(&signatures[7])];
+    let argument_signatures = [ArgumentSignature { argument_name: "message", signature: &signatures[8] }];
+    for packet in [
+        ClientboundPlay::PlayerChat(PlayPlayerChat {
+            sender: uuid::Uuid::from_u128(1),
+            index: 0,
+            message_signature: Some(&signatures[6]),
+            message: "hello",
+            timestamp: 1700000000000,
+            salt: -5,
+            previous_messages: Cow::Borrowed(&previous_messages),
+            unsigned_content: None,
+            filter: FilterMask::PartiallyFiltered(Cow::Owned(vec![0b11])),
+            chat_type: 0,
+            sender_name: text_component("jenya705"),
+            target_name: None,
+        }),
+        ClientboundPlay::DeleteMessage(PlayDeleteMessage { signature: PackedMessageSignature::Id(0) }),
+        ClientboundPlay::SystemChat(PlaySystemChat { content: text_component("Welcome"), overlay: true }),
+    ] {
+        let mut write = Vec::new();
+        packet.write_packet(&mut write).unwrap();
+        writes.push((Some(packet), None, write));
+    }
+    for packet in [
+        ServerboundPlay::SignedChatCommand(PlaySignedChatCommand {
+            command: "msg jenya705 hello",
+            timestamp: 1700000000000,
+            salt: 3,
+            argument_signatures: Cow::Borrowed(&argument_signatures),
+            last_seen: update,
+        }),
+        ServerboundPlay::ChatMessage(PlayChatMessage {
+            message: "hello",
+            timestamp: 1700000000000,
+            salt: 3,
+            signature: None,
+            last_seen: update,
+        }),
+    ] {
+        let mut write = Vec::new();
+        packet.write_packet(&mut write).unwrap();
+        writes.push((None, Some(packet), write));
+    }
+    for (clientbound, serverbound, write) in &writes {
+        let frame = PacketFrame::new(write).unwrap();
+        match (clientbound, serverbound) {
+            (Some(packet), _) => assert_eq!(&ClientboundPlay::read_packet(frame).unwrap(), packet),
+            (_, Some(packet)) => assert_eq!(&ServerboundPlay::read_packet(frame).unwrap(), packet),
+            _ => unreachable!(),
+        }
+    }
+    assert_eq!(writes[1].2, &[0x1C, 0x01]);
+}
+
+#[cfg(all(feature = "packet_default", feature = "chat-signing"))]
+#[test]
+fn signing_tests() {
+    use rsa::{Pkcs1v15Sign, RsaPrivateKey};
+    use rsa::pkcs8::EncodePublicKey;
+    use sha1::Sha1;
+    use sha2::{Digest, Sha256};
+    use crate::packet_chat::*;
+    use crate::packet_default::*;
+    use crate::packet_signing::*;
+    let mut rng = rand::thread_rng();
+    let services_key = RsaPrivateKey::new(&mut rng, 1024).unwrap();
+    let player_key = RsaPrivateKey::new(&mut rng, 1024).unwrap();
+    let services_der = services_key.to_public_key().to_public_key_der().unwrap();
+    let player_der = player_key.to_public_key().to_public_key_der().unwrap();
+    let player = uuid::Uuid::from_u128(1);
+    let expires_at = 1700000000000;
+    let mut payload = player.as_bytes().to_vec();
+    payload.extend_from_slice(&i64::to_be_bytes(expires_at));
+    payload.extend_from_slice(player_der.as_bytes());
+    let key_signature = services_key.sign(Pkcs1v15Sign::new::<Sha1>(), &Sha1::digest(&payload)).unwrap();
+    let keys = ServicesKeys::new().with_der(services_der.as_bytes()).unwrap();
+    let data = LoginStartSignatureData {
+        timestamp: expires_at,
+        public_key: player_der.as_bytes(),
+        signature: &key_signature,
+    };
+    let public_key = keys.verify_login(player, &data).unwrap();
+    assert_eq!(public_key.is_expired(expires_at - 1), false);
+    assert_eq!(public_key.is_expired(expires_at + 1), true);
+    assert_eq!(keys.verify_login(uuid::Uuid::from_u128(2), &data).is_err(), true);
+    let session = PlayPlayerSession {
+        session_id: uuid::Uuid::from_u128(3),
+        expires_at: expires_at + 1,
+        public_key: player_der.as_bytes(),
+        key_signature: &key_signature,
+    };
+    assert_eq!(keys.verify_session(player, &session).is_err(), true);
+
+    let last_seen = [[7; MESSAGE_SIGNATURE_LENGTH]];
+    let body = SignedMessageBody {
+        sender: player,
+        session_id: session.session_id,
+        index: 0,
+        salt: 3,
+        timestamp: 1700000000999,
+        message: "hello",
+        last_seen: &last_seen,
+    };
+    let data = body.signed_data();
+    assert_eq!(data.len(), 4 + 16 + 16 + 4 + 8 + 8 + 4 + 5 + 4 + MESSAGE_SIGNATURE_LENGTH);
+    assert_eq!(data[48..56], i64::to_be_bytes(1700000000));
+    let signature = player_key.sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(&data)).unwrap();
+    assert_eq!(public_key.verify_message(&body, &signature).is_ok(), true);
+    assert_eq!(public_key.verify_message(&SignedMessageBody { index: 1, ..body.clone() }, &signature).is_err(), true);
+    assert_eq!(public_key.verify_message(&SignedMessageBody { last_seen: &[], ..body }, &signature).is_err(), true);
+}
+
+#[cfg(all(feature = "packet_default", feature = "fastnbt"))]
+#[test]
+fn command_tests() {
+    use bird_chat::identifier::Identifier;
+    use crate::packet_command::*;
+    use crate::packet_default::*;
+    let graph = CommandGraph::new()
+        .then(CommandNode::literal("tp")
+            .then(CommandNode::argument("targets", ArgumentParser::Entity { single: false, players_only: false })
+                .then(CommandNode::argument("location", ArgumentParser::raw(10)).executes())))
+        .then(CommandNode::literal("teleport").redirect(["tp"]))
+        .then(CommandNode::literal("give")
+            .then(CommandNode::argument("count", ArgumentParser::Integer { min: Some(1), max: Some(64) })
+                .suggests(Identifier::new_fulled("minecraft:ask_server").unwrap())
+                .executes()))
+        .then(CommandNode::literal("execute")
+            .then(CommandNode::literal("run").redirect(Vec::<&str>::new())));
+    let flat = graph.flatten().unwrap();
+    assert_eq!(flat.nodes.len(), 9);
+    assert_eq!(flat.nodes[0].children, vec![1, 4, 5, 7]);
+    assert_eq!(flat.nodes[4].redirect, Some(1));
+    assert_eq!(flat.nodes[8].redirect, Some(0));
+    assert_eq!(CommandGraph::unflatten(&flat).unwrap(), graph);
+    assert_eq!(graph.find(&["tp", "targets", "location"]).unwrap().executable, true);
+    let mut writes = Vec::new();
+    for packet in [
+        ClientboundPlay::Commands(PlayCommands { graph: graph.clone() }),
+        ClientboundPlay::Commands(PlayCommands { graph: CommandGraph::new().then(CommandNode::literal("help").executes()) }),
+        ClientboundPlay::CommandSuggestionsResponse(PlayCommandSuggestionsResponse {
+            transaction_id: 1,
+            start: 4,
+            length: 2,
+            matches: Cow::Owned(vec![
+                CommandSuggestion { text: "jenya705", tooltip: Some(text_component("Player")) },
+                CommandSuggestion { text: "@a", tooltip: None },
+            ]),
+        }),
+    ] {
+        let mut write = Vec::new();
+        packet.write_packet(&mut write).unwrap();
+        writes.push((packet, write));
+    }
+    for (packet, write) in &writes {
+        assert_eq!(&ClientboundPlay::read_packet(PacketFrame::new(write).unwrap()).unwrap(), packet);
+    }
+    assert_eq!(writes[1].1, &[0x11, 0x02, 0x00, 0x01, 0x01, 0x05, 0x00, 0x04, b'h', b'e', b'l', b'p', 0x00]);
+    let request = PlayCommandSuggestionsRequest { transaction_id: 1, text: "/tp je" };
+    let mut write = Vec::new();
+    ServerboundPlay::CommandSuggestionsRequest(request.clone()).write_packet(&mut write).unwrap();
+    assert_eq!(
+        ServerboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap(),
+        ServerboundPlay::CommandSuggestionsRequest(request),
+    );
+
+    let mut write = Vec::new();
+    ArgumentParser::Raw { id: 70, properties: Cow::Borrowed(&[1, 2]) }.write(&mut write).unwrap();
+    assert_eq!(write, &[70, 1, 2]);
+    let error = CommandGraph::new().then(CommandNode::literal("tp")).then(CommandNode::literal("tp")).flatten().unwrap_err();
+    assert_eq!(error.to_string(), "Duplicate command node tp");
+    let error = CommandGraph::new().then(CommandNode::literal("tp").redirect(["teleport"])).flatten().unwrap_err();
+    assert_eq!(error.to_string(), "Redirect to unknown command node teleport");
+    let mut flat = CommandGraph::new().then(CommandNode::literal("help")).flatten().unwrap();
+    flat.nodes[1].children.push(1);
+    assert_eq!(CommandGraph::unflatten(&flat).is_err(), true);
+
+    // Literals nested into each other, the deepest one redirects to the root
+    let chain = |depth: usize| {
+        let mut nodes: Vec<FlatCommandNode> = (0..=depth as i32).map(|index| FlatCommandNode {
+            kind: CommandNodeKind::Literal(Cow::Borrowed("a")),
+            executable: false,
+            redirect: None,
+            children: vec![index + 1],
+        }).collect();
+        nodes[0].kind = CommandNodeKind::Root;
+        nodes[depth].children.clear();
+        nodes[depth].redirect = Some(1);
+        FlatCommandGraph { nodes, root_index: 0 }
+    };
+    let deepest = CommandGraph::unflatten(&chain(MAX_COMMAND_DEPTH)).unwrap();
+    let path = vec!["a"; MAX_COMMAND_DEPTH];
+    assert_eq!(deepest.find(&path).unwrap().redirect, Some(vec![Cow::Borrowed("a")]));
+    assert_eq!(deepest.flatten().unwrap(), chain(MAX_COMMAND_DEPTH));
+    let error = CommandGraph::unflatten(&chain(MAX_COMMAND_DEPTH + 1)).unwrap_err();
+    assert_eq!(error.to_string(), "Command graph is deeper than 256");
+    let mut write = vec![0x11];
+    chain(30_000).write(&mut write).unwrap();
+    let error = ClientboundPlay::read_packet(PacketFrame::new(&write).unwrap()).unwrap_err();
+    assert_eq!(error.to_string(), "Command graph is deeper than 256");
+    let too_deep = (0..=MAX_COMMAND_DEPTH)
+        .fold(CommandNode::literal("a"), |node, _| CommandNode::literal("a").then(node));
+    assert_eq!(CommandGraph::new().then(too_deep).flatten().is_err(), true);
+}
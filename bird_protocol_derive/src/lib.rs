@@ -4,8 +4,9 @@ mod write;
 mod util;
 mod read;
 mod packet;
+mod packet_set;
 
-#[proc_macro_derive(PacketWritable, attributes(variant, var, order, lifetime, enum_type, enum_variant, value))]
+#[proc_macro_derive(PacketWritable, attributes(variant, var, order, lifetime, enum_type, enum_variant, value, since, until, versioned))]
 pub fn packet_writable(args: proc_macro::TokenStream) -> proc_macro::TokenStream {
     match write::write_impl(&parse_macro_input!(args as DeriveInput)) {
         Ok(ts) => ts,
@@ -13,7 +14,7 @@ pub fn packet_writable(args: proc_macro::TokenStream) -> proc_macro::TokenStream
     }.into()
 }
 
-#[proc_macro_derive(PacketReadable, attributes(variant, var, order, lifetime, enum_type, enum_variant, value))]
+#[proc_macro_derive(PacketReadable, attributes(variant, var, order, lifetime, enum_type, enum_variant, value, since, until, versioned))]
 pub fn packet_readable(args: proc_macro::TokenStream) -> proc_macro::TokenStream {
     match read::read_impl(&parse_macro_input!(args as DeriveInput)) {
         Ok(ts) => ts,
@@ -27,4 +28,12 @@ pub fn packet(args: proc_macro::TokenStream) -> proc_macro::TokenStream {
         Ok(ts) => ts,
         Err(err) => err.into_compile_error(),
     }.into()
+}
+
+#[proc_macro_derive(PacketSet, attributes(packet_set))]
+pub fn packet_set(args: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match packet_set::packet_set_impl(&parse_macro_input!(args as DeriveInput)) {
+        Ok(ts) => ts,
+        Err(err) => err.into_compile_error(),
+    }.into()
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::DeriveInput;
-use crate::util::{get_bird_protocol_crate, PacketAttributes};
+use crate::util::{get_bird_protocol_crate, PacketAttributes, PacketId};
 
 pub fn packet_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
     let PacketAttributes { bound, state, id } =
@@ -9,6 +9,27 @@ pub fn packet_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
             Some(attr) => attr.parse_args()?,
             None => return Err(syn::Error::new(Span::call_site(), "packet attribute is not found"))
         };
+    let (id, id_for) = match id {
+        PacketId::Fixed(id) => (id.clone(), quote! { std::option::Option::Some(Self::ID) }),
+        PacketId::Versioned(ids) => {
+            let last_id = ids.iter().rev().find_map(|(_, id)| id.clone()).unwrap();
+            let checks = ids.iter().rev().map(|(version, id)| {
+                let id = match id {
+                    Some(id) => quote! { std::option::Option::Some(( #id ) as i32) },
+                    None => quote! { std::option::Option::None },
+                };
+                quote! {
+                    if version >= #version {
+                        return #id;
+                    }
+                }
+            });
+            (last_id, quote! {
+                #( #checks )*
+                std::option::Option::None
+            })
+        }
+    };
     let DeriveInput { ident, generics, .. } = args;
     let protocol_crate = get_bird_protocol_crate();
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
@@ -32,5 +53,17 @@ pub fn packet_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
                 Self::ID
             }
         }
+
+        impl #impl_generics #protocol_crate ::packet::VersionedPacket for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn id_for(version: i32) -> std::option::Option<i32> {
+                #id_for
+            }
+        }
+
+        impl #impl_generics #protocol_crate ::packet::StatePacket<
+            #protocol_crate ::packet::marker:: #state,
+            #protocol_crate ::packet::marker:: #bound,
+        > for #ident #ty_generics #where_clause {}
     })
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Data, DeriveInput, Fields};
+use crate::util::{add_trait_lifetime, get_bird_protocol_crate, get_lifetimes, PacketSetAttributes};
+
+pub fn packet_set_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
+    let PacketSetAttributes { bound, state } =
+        match args.attrs.iter().find(|attr| attr.path.is_ident("packet_set")) {
+            Some(attr) => attr.parse_args()?,
+            None => return Err(syn::Error::new(Span::call_site(), "packet_set attribute is not found"))
+        };
+    let data_enum = match args.data {
+        Data::Enum(ref data_enum) => data_enum,
+        _ => return Err(syn::Error::new(Span::call_site(), "Only enums are supported by PacketSet macro")),
+    };
+    let (add_lifetime, lifetime) = {
+        let lifetimes = get_lifetimes(&args.generics);
+        match lifetimes.len() {
+            0 => (true, quote! {'a}),
+            1 => (false, lifetimes.first().unwrap().lifetime.to_token_stream()),
+            _ => return Err(syn::Error::new(
+                Span::call_site(), "PacketSet supports only one lifetime",
+            ))
+        }
+    };
+    let protocol_crate = get_bird_protocol_crate();
+    let mut types = Vec::new();
+    let mut idents = Vec::new();
+    // Cfg attributes of the variants are repeated on everything generated for them
+    let mut cfgs = Vec::new();
+    for variant in &data_enum.variants {
+        match variant.fields {
+            Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
+                types.push(unnamed.unnamed.first().unwrap().ty.clone());
+                idents.push(variant.ident.clone());
+                cfgs.push(variant.attrs.iter().filter(|attr| attr.path.is_ident("cfg")).collect::<Vec<_>>());
+            }
+            _ => return Err(syn::Error::new(
+                variant.ident.span(), "Variant of PacketSet must wrap exactly one packet",
+            ))
+        }
+    }
+    let DeriveInput { ident, generics, .. } = args;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut cloned_generics = generics.clone();
+    let impl_generics = match add_lifetime {
+        true => {
+            add_trait_lifetime(&mut cloned_generics, quote! {'a});
+            cloned_generics.split_for_impl().0
+        }
+        false => impl_generics,
+    };
+    Ok(quote! {
+        const _: () = {
+            fn __assert_state_packet<T: #protocol_crate ::packet::StatePacket<
+                #protocol_crate ::packet::marker:: #state,
+                #protocol_crate ::packet::marker:: #bound,
+            >>() {}
+
+            fn __assert_packets #impl_generics () #where_clause {
+                #( #(#cfgs)* __assert_state_packet::< #types >(); )*
+            }
+        };
+
+        impl #impl_generics #protocol_crate ::packet_set::PacketSet< #lifetime > for #ident #ty_generics #where_clause {
+            fn state() -> #protocol_crate ::packet::PacketState {
+                #protocol_crate ::packet::PacketState:: #state
+            }
+
+            fn bound() -> #protocol_crate ::packet::PacketBound {
+                #protocol_crate ::packet::PacketBound:: #bound
+            }
+
+            fn id(&self) -> i32 {
+                match self {
+                    #( #(#cfgs)* Self:: #idents (_) => < #types as #protocol_crate ::packet::Packet>::id(), )*
+                }
+            }
+
+            fn read_packet(
+                frame: #protocol_crate ::packet_frame::PacketFrame< #lifetime >
+            ) -> Result<Self, #protocol_crate ::packet_set::PacketSetError< #lifetime >> {
+                #(
+                    #(#cfgs)*
+                    if frame.id == < #types as #protocol_crate ::packet::Packet>::id() {
+                        return Ok(Self:: #idents (frame.read_packet::< #types >()?));
+                    }
+                )*
+                Err(#protocol_crate ::packet_set::PacketSetError::UnknownId(
+                    #protocol_crate ::packet_set::UnknownPacketId {
+                        state: #protocol_crate ::packet::PacketState:: #state,
+                        bound: #protocol_crate ::packet::PacketBound:: #bound,
+                        id: frame.id,
+                        body: frame.body,
+                    }
+                ))
+            }
+
+            fn id_for(&self, version: i32) -> std::option::Option<i32> {
+                match self {
+                    #(
+                        #(#cfgs)*
+                        Self:: #idents (_) => < #types as #protocol_crate ::packet::VersionedPacket>::id_for(version),
+                    )*
+                }
+            }
+
+            fn read_packet_versioned(
+                frame: #protocol_crate ::packet_frame::PacketFrame< #lifetime >, version: i32,
+            ) -> Result<Self, #protocol_crate ::packet_set::PacketSetError< #lifetime >> {
+                #(
+                    #(#cfgs)*
+                    if std::option::Option::Some(frame.id) == < #types as #protocol_crate ::packet::VersionedPacket>::id_for(version) {
+                        return Ok(Self:: #idents (frame.read_packet_versioned::< #types >(version)?));
+                    }
+                )*
+                Err(#protocol_crate ::packet_set::PacketSetError::UnknownId(
+                    #protocol_crate ::packet_set::UnknownPacketId {
+                        state: #protocol_crate ::packet::PacketState:: #state,
+                        bound: #protocol_crate ::packet::PacketBound:: #bound,
+                        id: frame.id,
+                        body: frame.body,
+                    }
+                ))
+            }
+
+            fn write_packet_versioned<W>(&self, write: &mut W, version: i32) -> Result<(), anyhow::Error>
+                where W: #protocol_crate ::packet::PacketWrite {
+                let id = self.id_for(version)
+                    .ok_or_else(|| anyhow::anyhow!("Packet does not exist in protocol version {}", version))?;
+                < #protocol_crate ::packet_types::VarInt as #protocol_crate ::packet::PacketVariantWritable<i32>>
+                    ::write_variant(&id, write)?;
+                match self {
+                    #(
+                        #(#cfgs)*
+                        Self:: #idents (ref packet) =>
+                            #protocol_crate ::packet::PacketVersionedWritable::write_versioned(packet, write, version),
+                    )*
+                }
+            }
+
+            fn write_packet<W>(&self, write: &mut W) -> Result<(), anyhow::Error>
+                where W: #protocol_crate ::packet::PacketWrite {
+                match self {
+                    #(
+                        #(#cfgs)*
+                        Self:: #idents (ref packet) => <
+                            #protocol_crate ::packet_types::PacketVariant as
+                            #protocol_crate ::packet::PacketVariantWritable< #types >
+                        >::write_variant(packet, write),
+                    )*
+                }
+            }
+        }
+    })
+}
@@ -2,11 +2,12 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{Data, DeriveInput, Field, Fields, Path};
 use syn::spanned::Spanned;
-use crate::util::{add_trait_lifetime, DATA_ATTRIBUTES, DataAttributes, FieldAttributes, FieldVisitor, get_attributes, get_bird_protocol_crate, get_lifetimes, VariantAttributes, VariantVisitor, visit_derive_input, visit_fields};
+use crate::util::{add_trait_lifetime, DATA_ATTRIBUTES, DataAttributes, FieldAttributes, FieldVisitor, get_attributes, get_bird_protocol_crate, get_lifetimes, get_option_inner, VariantAttributes, VariantVisitor, version_condition, visit_derive_input, visit_fields};
 
 pub struct ReadableVariantVisitor {
     pub data_attributes: DataAttributes,
     pub lifetime: TokenStream,
+    pub version: TokenStream,
     pub variant_creators: Vec<(Option<TokenStream>, TokenStream)>,
 }
 
@@ -16,6 +17,7 @@ pub struct ReadableFieldVisitor {
     values: Vec<TokenStream>,
     named: bool,
     lifetime: TokenStream,
+    version: TokenStream,
 }
 
 impl VariantVisitor for ReadableVariantVisitor {
@@ -28,7 +30,7 @@ impl VariantVisitor for ReadableVariantVisitor {
                     _ => false
                 };
                 let mut field_visitor = ReadableFieldVisitor::new(
-                    named, self.lifetime.clone(),
+                    named, self.lifetime.clone(), self.version.clone(),
                 );
                 visit_fields(fields, &mut field_visitor)?;
                 let (reads, values) = field_visitor.into_pieces();
@@ -52,13 +54,14 @@ impl VariantVisitor for ReadableVariantVisitor {
 }
 
 impl ReadableFieldVisitor {
-    pub fn new(named: bool, lifetime: TokenStream) -> Self {
+    pub fn new(named: bool, lifetime: TokenStream, version: TokenStream) -> Self {
         Self {
             raw_reads: vec![],
             ordered_reads: vec![],
             values: vec![],
             named,
             lifetime,
+            version,
         }
     }
 
@@ -78,8 +81,25 @@ impl FieldVisitor for ReadableFieldVisitor {
             format!("__{}", ident.to_string()).as_str(), ident.span(),
         );
         let Field { ty, .. } = field;
-        let value_read = read_statement(&quote! {#ty}, &attributes.variant, &self.lifetime)?;
-        let read = quote! { let #value_ident = #value_read };
+        let condition = version_condition(&attributes, &self.version);
+        let ty = match condition {
+            Some(_) => get_option_inner(ty)?,
+            None => ty,
+        };
+        let version = match attributes.versioned {
+            true => Some(&self.version),
+            false => None,
+        };
+        let value_read = read_statement(&quote! {#ty}, &attributes.variant, &self.lifetime, version)?;
+        let read = match condition {
+            Some(condition) => quote! {
+                let #value_ident = match #condition {
+                    true => std::option::Option::Some(#value_read),
+                    false => std::option::Option::None,
+                }
+            },
+            None => quote! { let #value_ident = #value_read },
+        };
         match attributes.order {
             Some(index) => self.ordered_reads.push((index, read)),
             None => self.raw_reads.push(read)
@@ -92,14 +112,23 @@ impl FieldVisitor for ReadableFieldVisitor {
     }
 }
 
-pub fn read_statement(ty: &TokenStream, variant: &Option<TokenStream>, lifetime: &TokenStream) -> syn::Result<TokenStream> {
+pub fn read_statement(
+    ty: &TokenStream, variant: &Option<TokenStream>, lifetime: &TokenStream, version: Option<&TokenStream>,
+) -> syn::Result<TokenStream> {
     let protocol_crate = get_bird_protocol_crate();
-    Ok(match variant {
-        Some(ref variant) => quote! {
+    Ok(match (variant, version) {
+        (Some(_), Some(_)) => return Err(syn::Error::new(
+            ty.span(), "versioned attribute can not be used with variant",
+        )),
+        (Some(ref variant), None) => quote! {
             < #variant as #protocol_crate ::packet::PacketVariantReadable< #lifetime , #ty >>
             ::read_variant(read)?
         },
-        None => quote! {
+        (None, Some(version)) => quote! {
+            < #ty as #protocol_crate ::packet::PacketVersionedReadable< #lifetime >>
+            ::read_versioned(read, #version)?
+        },
+        (None, None) => quote! {
             < #ty as #protocol_crate ::packet::PacketReadable< #lifetime >>::read(read)?
         }
     })
@@ -126,9 +155,45 @@ pub fn read_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
     if let Data::Union(_) = args.data {
         return Err(syn::Error::new(Span::call_site(), "union type is not supported"));
     }
+    let protocol_crate = get_bird_protocol_crate();
+    // Not versioned reading is reading of the newest version
+    let body = read_body(args, &data_attributes, &lifetime, quote! { i32::MAX })?;
+    let versioned_body = read_body(args, &data_attributes, &lifetime, quote! { __version })?;
+    let DeriveInput { ident, generics, .. } = args;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut cloned_generics = generics.clone();
+    let impl_generics = match add_lifetime {
+        true => {
+            add_trait_lifetime(&mut cloned_generics, quote! {'a});
+            cloned_generics.split_for_impl().0
+        }
+        false => impl_generics,
+    };
+    Ok(quote! {
+        impl #impl_generics #protocol_crate ::packet::PacketReadable< #lifetime > for #ident #ty_generics #where_clause {
+            fn read<R>(read: &mut R) -> Result<Self, #protocol_crate ::packet::PacketReadableError>
+            where R: #protocol_crate ::packet::PacketRead< #lifetime > {
+                #body
+            }
+        }
+
+        impl #impl_generics #protocol_crate ::packet::PacketVersionedReadable< #lifetime > for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn read_versioned<R>(read: &mut R, __version: i32) -> Result<Self, #protocol_crate ::packet::PacketReadableError>
+            where R: #protocol_crate ::packet::PacketRead< #lifetime > {
+                #versioned_body
+            }
+        }
+    })
+}
+
+fn read_body(
+    args: &DeriveInput, data_attributes: &DataAttributes, lifetime: &TokenStream, version: TokenStream,
+) -> syn::Result<TokenStream> {
     let mut variant_visitor = ReadableVariantVisitor {
-        data_attributes,
+        data_attributes: data_attributes.clone(),
         lifetime: lifetime.clone(),
+        version,
         variant_creators: vec![],
     };
     visit_derive_input(args, &mut variant_visitor)?;
@@ -152,7 +217,8 @@ pub fn read_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
             let value_read_ts = read_statement(
                 ty,
                 variant,
-                &lifetime,
+                lifetime,
+                None,
             )?;
             let mut values = quote! {};
             let mut counter = 0usize;
@@ -185,22 +251,5 @@ pub fn read_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
         }
         _ => unreachable!()
     };
-    let DeriveInput { ident, generics, .. } = args;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let mut cloned_generics = generics.clone();
-    let impl_generics = match add_lifetime {
-        true => {
-            add_trait_lifetime(&mut cloned_generics, quote! {'a});
-            cloned_generics.split_for_impl().0
-        }
-        false => impl_generics,
-    };
-    Ok(quote! {
-        impl #impl_generics #protocol_crate ::packet::PacketReadable< #lifetime > for #ident #ty_generics #where_clause {
-            fn read<R>(read: &mut R) -> Result<Self, #protocol_crate ::packet::PacketReadableError>
-            where R: #protocol_crate ::packet::PacketRead< #lifetime > {
-                #body
-            }
-        }
-    })
+    Ok(body)
 }
\ No newline at end of file
@@ -2,14 +2,14 @@ use std::collections::HashMap;
 use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::{quote, ToTokens};
-use syn::{Attribute, Data, DataEnum, DataStruct, DeriveInput, Expr, ExprAssign, Field, Fields, GenericParam, Generics, LifetimeDef, Lit, parse_quote, Path, PathArguments, PathSegment, Token};
+use syn::{Attribute, braced, Data, DataEnum, DataStruct, DeriveInput, Expr, ExprAssign, Field, Fields, GenericArgument, GenericParam, Generics, LifetimeDef, Lit, LitInt, parse_quote, Path, PathArguments, PathSegment, Token, Type};
 use syn::parse::{Parse, ParseStream};
 use syn::parse_quote::ParseQuote;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::token::Colon2;
+use syn::token::{Brace, Colon2};
 
-pub const FIELD_ATTRIBUTES: &[&str] = &["variant", "var", "order"];
+pub const FIELD_ATTRIBUTES: &[&str] = &["variant", "var", "order", "since", "until", "versioned"];
 pub const DATA_ATTRIBUTES: &[&str] = &["lifetime", "enum_type", "enum_variant"];
 pub const VARIANT_ATTRIBUTES: &[&str] = &["value"];
 
@@ -17,6 +17,9 @@ pub const VARIANT_ATTRIBUTES: &[&str] = &["value"];
 pub struct FieldAttributes {
     pub order: Option<usize>,
     pub variant: Option<TokenStream>,
+    pub since: Option<TokenStream>,
+    pub until: Option<TokenStream>,
+    pub versioned: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -35,7 +38,22 @@ pub struct VariantAttributes {
 pub struct PacketAttributes {
     pub bound: TokenStream,
     pub state: TokenStream,
-    pub id: TokenStream,
+    pub id: PacketId,
+}
+
+/// Id of the packet. Versioned ids are sorted by protocol version,
+/// each id is used from its version until the version of the next id.
+/// [None] id means that packet is removed since its version.
+#[derive(Debug, Clone)]
+pub enum PacketId {
+    Fixed(TokenStream),
+    Versioned(Vec<(i32, Option<TokenStream>)>),
+}
+
+#[derive(Debug, Clone)]
+pub struct PacketSetAttributes {
+    pub bound: TokenStream,
+    pub state: TokenStream,
 }
 
 pub trait FieldVisitor {
@@ -161,7 +179,11 @@ pub fn get_attributes<'a>(names: &[&'a str], attributes: &Vec<Attribute>) -> syn
     for attribute in attributes {
         for name in names {
             if attribute.path.is_ident(name) {
-                res.insert(*name, attribute.parse_args()?);
+                // Attribute without arguments is a flag
+                res.insert(*name, match attribute.tokens.is_empty() {
+                    true => parse_quote! { true },
+                    false => attribute.parse_args()?,
+                });
                 break;
             }
         }
@@ -190,6 +212,11 @@ impl TryFrom<HashMap<&str, Expr>> for FieldAttributes {
             variant: value.get("variant")
                 .or(value.get("var"))
                 .map(|expr| expr.to_token_stream()),
+            since: value.get("since")
+                .map(|expr| expr.to_token_stream()),
+            until: value.get("until")
+                .map(|expr| expr.to_token_stream()),
+            versioned: value.contains_key("versioned"),
         })
     }
 }
@@ -271,14 +298,99 @@ fn collect_map_attribute(input: ParseStream) -> syn::Result<HashMap<String, Expr
     Ok(result)
 }
 
+struct VersionedId {
+    version: i32,
+    id: Expr,
+}
+
+impl Parse for VersionedId {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let version: LitInt = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        Ok(Self { version: version.base10_parse()?, id: input.parse()? })
+    }
+}
+
 impl Parse for PacketAttributes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut bound = None;
+        let mut state = None;
+        let mut id = None;
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match name.to_string().as_str() {
+                "bound" => bound = Some(input.parse::<Expr>()?.to_token_stream()),
+                "state" => state = Some(input.parse::<Expr>()?.to_token_stream()),
+                "id" => id = Some(match input.peek(Brace) {
+                    true => {
+                        let content;
+                        braced!(content in input);
+                        let punctuated: Punctuated<VersionedId, Token![,]> = Punctuated::parse_terminated(&content)?;
+                        let mut ids: Vec<(i32, Option<TokenStream>)> = punctuated.into_iter()
+                            .map(|versioned| (versioned.version, match versioned.id {
+                                Expr::Path(ref path) if path.path.is_ident("None") => None,
+                                ref id => Some(id.to_token_stream()),
+                            }))
+                            .collect();
+                        if ids.iter().all(|(_, id)| id.is_none()) {
+                            return Err(syn::Error::new(name.span(), "At least one versioned id must be provided"));
+                        }
+                        ids.sort_by_key(|(version, _)| *version);
+                        PacketId::Versioned(ids)
+                    }
+                    false => PacketId::Fixed(input.parse::<Expr>()?.to_token_stream()),
+                }),
+                _ => return Err(syn::Error::new(name.span(), "Unknown packet attribute")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let missing = |name: &str| syn::Error::new(input.span(), format!("{} is not provided", name));
+        Ok(Self {
+            id: id.ok_or_else(|| missing("id"))?,
+            bound: bound.ok_or_else(|| missing("bound"))?,
+            state: state.ok_or_else(|| missing("state"))?,
+        })
+    }
+}
+
+impl Parse for PacketSetAttributes {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let attrs = collect_map_attribute(input)?;
+        let get = |name: &str| attrs.get(name)
+            .map(|expr| expr.to_token_stream())
+            .ok_or_else(|| syn::Error::new(input.span(), format!("{} is not provided", name)));
         Ok(Self {
-            // TODO handling
-            id: attrs.get("id").unwrap().to_token_stream(),
-            bound: attrs.get("bound").unwrap().to_token_stream(),
-            state: attrs.get("state").unwrap().to_token_stream()
+            bound: get("bound")?,
+            state: get("state")?,
         })
     }
+}
+
+/// Returns `T` of `Option<T>` type.
+pub fn get_option_inner(ty: &Type) -> syn::Result<&Type> {
+    if let Type::Path(ref type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(ref arguments) = segment.arguments {
+                    if let Some(GenericArgument::Type(ref inner)) = arguments.args.first() {
+                        return Ok(inner);
+                    }
+                }
+            }
+        }
+    }
+    Err(syn::Error::new(ty.span(), "Field with since or until attribute must be Option"))
+}
+
+/// Returns condition that is true if field with the given attributes exists in the version.
+pub fn version_condition(attributes: &FieldAttributes, version: &TokenStream) -> Option<TokenStream> {
+    match (&attributes.since, &attributes.until) {
+        (Some(since), Some(until)) => Some(quote! { (#version >= (#since) && #version < (#until)) }),
+        (Some(since), None) => Some(quote! { (#version >= (#since)) }),
+        (None, Some(until)) => Some(quote! { (#version < (#until)) }),
+        (None, None) => None,
+    }
 }
\ No newline at end of file
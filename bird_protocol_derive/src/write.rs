@@ -1,24 +1,27 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{DeriveInput, Field, Fields, Path};
-use crate::util::{DATA_ATTRIBUTES, DataAttributes, FieldAttributes, FieldVisitor, get_attributes, get_bird_protocol_crate, VariantAttributes, VariantVisitor, visit_derive_input, visit_fields};
+use crate::util::{DATA_ATTRIBUTES, DataAttributes, FieldAttributes, FieldVisitor, get_attributes, get_bird_protocol_crate, get_option_inner, VariantAttributes, VariantVisitor, version_condition, visit_derive_input, visit_fields};
 
 pub struct WritableVariantVisitor {
     variants: TokenStream,
     data_attributes: DataAttributes,
+    version: TokenStream,
 }
 
 pub struct WritableFieldVisitor {
     fields: TokenStream,
     raw_writes: Vec<TokenStream>,
     ordered_writes: Vec<(usize, TokenStream)>,
+    version: TokenStream,
 }
 
 impl WritableVariantVisitor {
-    pub fn new(data_attributes: DataAttributes) -> Self {
+    pub fn new(data_attributes: DataAttributes, version: TokenStream) -> Self {
         Self {
             data_attributes,
             variants: quote! {},
+            version,
         }
     }
 
@@ -34,8 +37,7 @@ impl WritableVariantVisitor {
 impl VariantVisitor for WritableVariantVisitor {
     fn visit(&mut self, ident: Path, data_fields: &Fields,
              value: Option<TokenStream>, _attributes: VariantAttributes) -> syn::Result<()> {
-        let Self { variants, .. } = self;
-        let mut field_visitor = WritableFieldVisitor::new();
+        let mut field_visitor = WritableFieldVisitor::new(self.version.clone());
         visit_fields(data_fields, &mut field_visitor)?;
         let (fields, writes) = field_visitor.into_pieces();
         let fields = match data_fields {
@@ -58,6 +60,7 @@ impl VariantVisitor for WritableVariantVisitor {
                         variant,
                         ty,
                         &quote! { &((#value) as #ty) },
+                        None,
                     );
                     end_writes = quote! {
                         #write_ts
@@ -69,6 +72,7 @@ impl VariantVisitor for WritableVariantVisitor {
                         &None,
                         &end_variant,
                         &quote! { &((#value) as #end_variant) },
+                        None,
                     );
                     end_writes = quote! {
                         #write_ts
@@ -79,6 +83,7 @@ impl VariantVisitor for WritableVariantVisitor {
             None => end_writes = quote! { #( #writes )* },
         }
 
+        let variants = &mut self.variants;
         *variants = quote! {
             #variants
             #ident #fields => { #end_writes },
@@ -88,11 +93,12 @@ impl VariantVisitor for WritableVariantVisitor {
 }
 
 impl WritableFieldVisitor {
-    pub fn new() -> Self {
+    pub fn new(version: TokenStream) -> Self {
         Self {
             fields: quote! {},
             raw_writes: vec![],
             ordered_writes: vec![],
+            version,
         }
     }
 
@@ -111,18 +117,54 @@ impl FieldVisitor for WritableFieldVisitor {
         let Self {
             fields,
             raw_writes,
-            ordered_writes, ..
+            ordered_writes,
+            version,
         } = self;
         *fields = quote! {
             #fields
             ref #ident,
         };
         let Field { ty, .. } = field;
-        let write_ts = write_ts(
-            &attributes.variant,
-            &quote! { #ty },
-            &ident.to_token_stream(),
-        );
+        let condition = version_condition(&attributes, version);
+        let ty = match condition {
+            Some(_) => get_option_inner(ty)?,
+            None => ty,
+        };
+        let write_version = match attributes.versioned {
+            true => {
+                if attributes.variant.is_some() {
+                    return Err(syn::Error::new(ident.span(), "versioned attribute can not be used with variant"));
+                }
+                Some(&*version)
+            }
+            false => None,
+        };
+        let write_ts = match condition {
+            Some(condition) => {
+                let value_write = write_ts(
+                    &attributes.variant,
+                    &quote! { #ty },
+                    &quote! { __value },
+                    write_version,
+                );
+                quote! {
+                    if #condition {
+                        match #ident {
+                            std::option::Option::Some(ref __value) => { #value_write }
+                            std::option::Option::None => return Err(anyhow::Error::msg(
+                                concat!("Field ", stringify!(#ident), " is required in this protocol version")
+                            )),
+                        }
+                    }
+                }
+            }
+            None => write_ts(
+                &attributes.variant,
+                &quote! { #ty },
+                &ident.to_token_stream(),
+                write_version,
+            ),
+        };
         match attributes.order {
             Some(order) => ordered_writes.push((order, write_ts)),
             None => raw_writes.push(write_ts),
@@ -131,28 +173,37 @@ impl FieldVisitor for WritableFieldVisitor {
     }
 }
 
-fn write_ts(variant: &Option<TokenStream>, ty: &TokenStream, value: &TokenStream) -> TokenStream {
+fn write_ts(
+    variant: &Option<TokenStream>, ty: &TokenStream, value: &TokenStream, version: Option<&TokenStream>,
+) -> TokenStream {
     let protocol_crate = get_bird_protocol_crate();
-    match variant {
-        Some(ref variant) => quote! {
+    match (variant, version) {
+        (Some(ref variant), _) => quote! {
             < #variant as #protocol_crate ::packet::PacketVariantWritable< #ty >>
             ::write_variant( #value , write)?;
         },
-        None => quote! {
+        (None, Some(version)) => quote! {
+            < #ty as #protocol_crate ::packet::PacketVersionedWritable>::write_versioned( #value , write, #version)?;
+        },
+        (None, None) => quote! {
             < #ty as #protocol_crate ::packet::PacketWritable>::write( #value , write)?;
         }
     }
 }
 
 pub fn write_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
-    let data_attributes =
+    let data_attributes: DataAttributes =
         get_attributes(DATA_ATTRIBUTES, &args.attrs)?.try_into()?;
     let protocol_crate = get_bird_protocol_crate();
-    let mut visitor = WritableVariantVisitor::new(data_attributes);
+    // Not versioned writing is writing of the newest version
+    let mut visitor = WritableVariantVisitor::new(data_attributes.clone(), quote! { i32::MAX });
     visit_derive_input(args, &mut visitor)?;
+    let mut versioned_visitor = WritableVariantVisitor::new(data_attributes, quote! { __version });
+    visit_derive_input(args, &mut versioned_visitor)?;
     let DeriveInput { ident, generics, .. } = args;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let variants = visitor.get_variants();
+    let versioned_variants = versioned_visitor.get_variants();
     Ok(quote! {
         impl #impl_generics #protocol_crate ::packet::PacketWritable for #ident #ty_generics #where_clause {
             fn write<W>(&self, write: &mut W) -> Result<(), anyhow::Error>
@@ -163,5 +214,16 @@ pub fn write_impl(args: &DeriveInput) -> syn::Result<TokenStream> {
                 Ok(())
             }
         }
+
+        impl #impl_generics #protocol_crate ::packet::PacketVersionedWritable for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn write_versioned<W>(&self, write: &mut W, __version: i32) -> Result<(), anyhow::Error>
+                where W: #protocol_crate ::packet::PacketWrite {
+                match self {
+                    #versioned_variants
+                }
+                Ok(())
+            }
+        }
     })
 }
\ No newline at end of file